@@ -0,0 +1,54 @@
+// ─────────────────────────────────────────────────────────────────────────────
+// programs/trial/fuzz/fuzz_targets/curve_sequence.rs
+//
+// Fuzz target: arbitrary reserves and buy / sell sequences through the pure
+// curve functions, checked against the same invariant model as the proptest
+// suite (tests/common/mod.rs).
+//
+// Run (nightly):  cargo fuzz run curve_sequence
+// Shrink a crash: cargo fuzz tmin curve_sequence artifacts/curve_sequence/crash-…
+// Replay it:      cargo fuzz run curve_sequence artifacts/curve_sequence/minimized-…
+// ─────────────────────────────────────────────────────────────────────────────
+
+#![no_main]
+
+#[path = "../../tests/common/mod.rs"]
+mod common;
+
+use arbitrary::Arbitrary;
+use common::{run, Op};
+use libfuzzer_sys::fuzz_target;
+use trial2::{INITIAL_VIRTUAL_SOL_RESERVE, INITIAL_VIRTUAL_TOKEN_RESERVE};
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    /// None starts from the launch reserves
+    reserves: Option<(u64, u64)>,
+    ops: Vec<FuzzOp>,
+}
+
+#[derive(Arbitrary, Debug)]
+enum FuzzOp {
+    Buy(u64),
+    Sell(u16),
+}
+
+fuzz_target!(|input: Input| {
+    let (vsr, vtr) = match input.reserves {
+        // Both reserves must be non-zero for a curve to exist
+        Some((vsr, vtr)) if vsr > 0 && vtr > 0 => (vsr, vtr),
+        Some(_) => return,
+        None => (INITIAL_VIRTUAL_SOL_RESERVE, INITIAL_VIRTUAL_TOKEN_RESERVE),
+    };
+
+    let ops: Vec<Op> = input
+        .ops
+        .into_iter()
+        .map(|op| match op {
+            FuzzOp::Buy(sol_amount) => Op::Buy(sol_amount),
+            FuzzOp::Sell(share_bps) => Op::Sell(share_bps),
+        })
+        .collect();
+
+    run(vsr, vtr, &ops);
+});
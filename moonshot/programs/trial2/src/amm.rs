@@ -0,0 +1,318 @@
+// ─────────────────────────────────────────────────────────────────────────────
+// programs/trial/src/amm.rs
+//
+// Pure math for the post-graduation pool — no Solana, no Anchor, no accounts.
+// A plain constant-product pool (x × y = k) of real SOL against real tokens,
+// with LP shares. Same rules as curve.rs: every rounding goes the pool's way,
+// so k and the value behind each LP share can only grow.
+// Test with: cargo test (runs instantly, no blockchain needed)
+// ─────────────────────────────────────────────────────────────────────────────
+
+use crate::curve::{TradeError, BPS_DENOMINATOR, PRICE_SCALE};
+
+/// Swap fee, left in the pool for liquidity providers. 25 bps = 0.25%
+pub const POOL_FEE_BPS: u64 = 25;
+
+/// Reserves and LP shares of a pool — mirrors the on-chain Pool fields
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolState {
+    pub sol_reserve: u64,
+    pub token_reserve: u64,
+    /// Every LP share, including the locked migration liquidity
+    pub lp_supply: u64,
+}
+
+/// What a pool operation moves
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolOutcome {
+    /// Lamports in (buy / deposit) or out (sell / withdrawal)
+    pub sol_amount: u64,
+    /// Tokens out (buy) or in (sell / deposit), or out (withdrawal)
+    pub token_amount: u64,
+    /// LP shares minted or burned (zero for swaps)
+    pub lp_amount: u64,
+    /// Pool reserves after the operation
+    pub state: PoolState,
+}
+
+// ─── Seeding from a graduated curve ───────────────────────────────────────────
+
+/// LP shares for the first deposit: ⌊√(sol × tokens)⌋, the geometric mean,
+/// so the share count doesn't depend on the price the pool opens at
+pub fn initial_liquidity(sol: u64, tokens: u64) -> u64 {
+    // √(u64 × u64) < 2^64, so the result always fits
+    isqrt(sol as u128 * tokens as u128) as u64
+}
+
+/// ⌊√n⌋ by Newton's method
+fn isqrt(n: u128) -> u128 {
+    if n < 2 { return n; }
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+// ─── Swaps ────────────────────────────────────────────────────────────────────
+
+/// Output of a swap of `amount_in` against (reserve_in, reserve_out) with the
+/// fee taken from the input:
+///   in_after_fee = amount_in × (10000 − fee_bps) / 10000
+///   amount_out   = ⌊reserve_out × in_after_fee / (reserve_in + in_after_fee)⌋
+pub fn amount_out(amount_in: u64, reserve_in: u64, reserve_out: u64, fee_bps: u64) -> Result<u64, TradeError> {
+    if amount_in == 0 { return Err(TradeError::ZeroAmount); }
+    if fee_bps >= BPS_DENOMINATOR { return Err(TradeError::InvalidFeeBps); }
+
+    let in_after_fee = amount_in as u128 * (BPS_DENOMINATOR - fee_bps) as u128;
+    let numerator = in_after_fee.checked_mul(reserve_out as u128).ok_or(TradeError::MathOverflow("amount_out"))?;
+    let denominator = (reserve_in as u128 * BPS_DENOMINATOR as u128)
+        .checked_add(in_after_fee)
+        .ok_or(TradeError::MathOverflow("amount_out"))?;
+
+    // < reserve_out, so it fits
+    Ok((numerator / denominator) as u64)
+}
+
+impl PoolState {
+    /// Spot price in PRICE_SCALE units, same as PricingCurve::spot_price
+    pub fn spot_price(&self) -> Option<u128> {
+        (self.sol_reserve as u128).checked_mul(PRICE_SCALE)?.checked_div(self.token_reserve as u128)
+    }
+
+    /// Buy tokens with `sol_in` lamports, fee included
+    pub fn swap_sol_for_tokens(&self, sol_in: u64, fee_bps: u64) -> Result<PoolOutcome, TradeError> {
+        let tokens_out = amount_out(sol_in, self.sol_reserve, self.token_reserve, fee_bps)?;
+        if tokens_out == 0 { return Err(TradeError::ZeroAmount); }
+
+        Ok(PoolOutcome {
+            sol_amount: sol_in,
+            token_amount: tokens_out,
+            lp_amount: 0,
+            state: PoolState {
+                sol_reserve: self.sol_reserve.checked_add(sol_in).ok_or(TradeError::MathOverflow("sol_reserve"))?,
+                token_reserve: self.token_reserve - tokens_out,
+                lp_supply: self.lp_supply,
+            },
+        })
+    }
+
+    /// Sell `tokens_in` tokens for SOL, fee included
+    pub fn swap_tokens_for_sol(&self, tokens_in: u64, fee_bps: u64) -> Result<PoolOutcome, TradeError> {
+        let sol_out = amount_out(tokens_in, self.token_reserve, self.sol_reserve, fee_bps)?;
+        if sol_out == 0 { return Err(TradeError::ZeroAmount); }
+
+        Ok(PoolOutcome {
+            sol_amount: sol_out,
+            token_amount: tokens_in,
+            lp_amount: 0,
+            state: PoolState {
+                sol_reserve: self.sol_reserve - sol_out,
+                token_reserve: self.token_reserve.checked_add(tokens_in).ok_or(TradeError::MathOverflow("token_reserve"))?,
+                lp_supply: self.lp_supply,
+            },
+        })
+    }
+
+    // ─── Liquidity ────────────────────────────────────────────────────────
+
+    /// Mint exactly `lp_amount` shares. The depositor pays ⌈share × reserve⌉
+    /// of each side, so existing shares never lose value to rounding.
+    pub fn deposit(&self, lp_amount: u64) -> Result<PoolOutcome, TradeError> {
+        if lp_amount == 0 { return Err(TradeError::ZeroAmount); }
+        if self.lp_supply == 0 { return Err(TradeError::InsufficientSol); }
+
+        let share = |reserve: u64| {
+            let amount = (lp_amount as u128 * reserve as u128).div_ceil(self.lp_supply as u128);
+            u64::try_from(amount).map_err(|_| TradeError::MathOverflow("deposit"))
+        };
+        let sol_amount = share(self.sol_reserve)?;
+        let token_amount = share(self.token_reserve)?;
+
+        Ok(PoolOutcome {
+            sol_amount,
+            token_amount,
+            lp_amount,
+            state: PoolState {
+                sol_reserve: self.sol_reserve.checked_add(sol_amount).ok_or(TradeError::MathOverflow("sol_reserve"))?,
+                token_reserve: self.token_reserve.checked_add(token_amount).ok_or(TradeError::MathOverflow("token_reserve"))?,
+                lp_supply: self.lp_supply.checked_add(lp_amount).ok_or(TradeError::MathOverflow("lp_supply"))?,
+            },
+        })
+    }
+
+    /// Burn `lp_amount` shares for ⌊share × reserve⌋ of each side. The pool
+    /// can never be emptied — the locked migration shares always remain.
+    pub fn withdraw(&self, lp_amount: u64) -> Result<PoolOutcome, TradeError> {
+        if lp_amount == 0 { return Err(TradeError::ZeroAmount); }
+        if lp_amount >= self.lp_supply { return Err(TradeError::InsufficientSol); }
+
+        // lp_amount < lp_supply, so each share is below its reserve
+        let share = |reserve: u64| (lp_amount as u128 * reserve as u128 / self.lp_supply as u128) as u64;
+        let sol_amount = share(self.sol_reserve);
+        let token_amount = share(self.token_reserve);
+        if sol_amount == 0 && token_amount == 0 { return Err(TradeError::ZeroAmount); }
+
+        Ok(PoolOutcome {
+            sol_amount,
+            token_amount,
+            lp_amount,
+            state: PoolState {
+                sol_reserve: self.sol_reserve - sol_amount,
+                token_reserve: self.token_reserve - token_amount,
+                lp_supply: self.lp_supply - lp_amount,
+            },
+        })
+    }
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::{execute_buy, ConstantProduct, CurveState, PricingCurve, GRADUATION_THRESHOLD, PLATFORM_FEE_BPS, TOKEN_TOTAL_SUPPLY};
+
+    // Helper: the pool a default launch migrates into
+    fn migrated_pool() -> (CurveState, PoolState) {
+        let curve = execute_buy(&ConstantProduct, &CurveState::initial(), 200_000_000_000, PLATFORM_FEE_BPS, GRADUATION_THRESHOLD)
+            .unwrap()
+            .state;
+        let sol = curve.real_sol_balance;
+        // Every token not in holders' hands: the unsold ones plus the rest of the supply
+        let tokens = TOKEN_TOTAL_SUPPLY - curve.tokens_sold;
+        (curve, PoolState { sol_reserve: sol, token_reserve: tokens, lp_supply: initial_liquidity(sol, tokens) })
+    }
+
+    fn k(pool: &PoolState) -> u128 {
+        pool.sol_reserve as u128 * pool.token_reserve as u128
+    }
+
+    // ─── 1. Migration ─────────────────────────────────────────────────────
+
+    #[test]
+    fn test_pool_opens_at_the_curves_final_price() {
+        let (curve, pool) = migrated_pool();
+        assert_eq!(pool.sol_reserve, GRADUATION_THRESHOLD);
+
+        let curve_price = ConstantProduct.spot_price(&curve).unwrap();
+        let pool_price = pool.spot_price().unwrap();
+        // With the default reserves, seeding the whole unsold supply lands
+        // within a basis point of the curve's price
+        let gap_bps = curve_price.abs_diff(pool_price) * BPS_DENOMINATOR as u128 / curve_price;
+        assert!(gap_bps <= 1, "curve {} vs pool {}", curve_price, pool_price);
+
+        // Default launch: ~206.9M tokens go in next to the 85 SOL, like pump.fun
+        assert!((206_000_000_000_000..207_000_000_000_000).contains(&pool.token_reserve), "{}", pool.token_reserve);
+    }
+
+    #[test]
+    fn test_initial_liquidity_is_the_geometric_mean() {
+        assert_eq!(initial_liquidity(4, 9), 6);
+        assert_eq!(initial_liquidity(10, 10), 10);
+        assert_eq!(initial_liquidity(2, 3), 2, "rounds down");
+        assert_eq!(initial_liquidity(u64::MAX, u64::MAX), u64::MAX);
+        assert_eq!(initial_liquidity(0, 1_000), 0);
+    }
+
+    // ─── 2. Swaps ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_amount_out_matches_constant_product_with_fee() {
+        // 1000 in at 25 bps → 997.5 after fee; 1_000_000 × 997.5 / (1_000_000 + 997.5)
+        assert_eq!(amount_out(1_000, 1_000_000, 1_000_000, 25), Ok(996));
+        assert_eq!(amount_out(1_000, 1_000_000, 1_000_000, 0), Ok(999));
+        assert_eq!(amount_out(0, 1_000, 1_000, 25), Err(TradeError::ZeroAmount));
+        assert_eq!(amount_out(1_000, 1_000, 1_000, BPS_DENOMINATOR), Err(TradeError::InvalidFeeBps));
+    }
+
+    #[test]
+    fn test_swaps_never_shrink_k() {
+        let (_, mut pool) = migrated_pool();
+        for i in 1..50u64 {
+            let before = k(&pool);
+            pool = if i % 3 == 0 {
+                pool.swap_tokens_for_sol(i * 3_000_000_000_000, POOL_FEE_BPS).unwrap().state
+            } else {
+                pool.swap_sol_for_tokens(i * 100_000_000, POOL_FEE_BPS).unwrap().state
+            };
+            assert!(k(&pool) >= before, "k shrank on swap {}", i);
+        }
+    }
+
+    #[test]
+    fn test_swap_round_trip_loses_the_fees() {
+        let (_, pool) = migrated_pool();
+        let bought = pool.swap_sol_for_tokens(1_000_000_000, POOL_FEE_BPS).unwrap();
+        let sold = bought.state.swap_tokens_for_sol(bought.token_amount, POOL_FEE_BPS).unwrap();
+
+        assert!(sold.sol_amount < 1_000_000_000);
+        // Roughly two 0.25% fees, the second one charged on slightly less
+        assert!(1_000_000_000 - sold.sol_amount >= 4_900_000, "lost {}", 1_000_000_000 - sold.sol_amount);
+    }
+
+    #[test]
+    fn test_swap_cannot_drain_a_reserve() {
+        let (_, pool) = migrated_pool();
+        let whale = pool.swap_sol_for_tokens(u64::MAX / 2, POOL_FEE_BPS).unwrap();
+        assert!(whale.state.token_reserve > 0);
+        let dump = pool.swap_tokens_for_sol(u64::MAX / 2, POOL_FEE_BPS).unwrap();
+        assert!(dump.state.sol_reserve > 0);
+    }
+
+    #[test]
+    fn test_dust_swap_is_rejected() {
+        let (_, pool) = migrated_pool();
+        // 1 token is worth far less than a lamport
+        assert_eq!(pool.swap_tokens_for_sol(1, POOL_FEE_BPS), Err(TradeError::ZeroAmount));
+    }
+
+    // ─── 3. Liquidity ─────────────────────────────────────────────────────
+
+    #[test]
+    fn test_deposit_then_withdraw_never_profits() {
+        let (_, pool) = migrated_pool();
+        for lp in [1, 1_000, 123_456_789, pool.lp_supply] {
+            let deposit = pool.deposit(lp).unwrap();
+            let withdraw = deposit.state.withdraw(lp).unwrap();
+            assert!(withdraw.sol_amount <= deposit.sol_amount, "lp {}", lp);
+            assert!(withdraw.token_amount <= deposit.token_amount, "lp {}", lp);
+            // The pool keeps any rounding dust
+            assert!(withdraw.state.sol_reserve >= pool.sol_reserve);
+            assert!(withdraw.state.token_reserve >= pool.token_reserve);
+            assert_eq!(withdraw.state.lp_supply, pool.lp_supply);
+        }
+    }
+
+    #[test]
+    fn test_deposit_keeps_the_price() {
+        let (_, pool) = migrated_pool();
+        let after = pool.deposit(pool.lp_supply / 2).unwrap().state;
+        let (before, now) = (pool.spot_price().unwrap(), after.spot_price().unwrap());
+        assert!(before.abs_diff(now) <= 1, "{} → {}", before, now);
+    }
+
+    #[test]
+    fn test_fees_accrue_to_lp_shares() {
+        let (_, pool) = migrated_pool();
+        let mut traded = pool.deposit(1_000_000_000).unwrap().state;
+        for _ in 0..20 {
+            let bought = traded.swap_sol_for_tokens(5_000_000_000, POOL_FEE_BPS).unwrap();
+            traded = bought.state.swap_tokens_for_sol(bought.token_amount, POOL_FEE_BPS).unwrap().state;
+        }
+        // Same shares, bigger k — each share is backed by more than before the trading
+        let fresh = pool.deposit(1_000_000_000).unwrap().state;
+        assert_eq!(traded.lp_supply, fresh.lp_supply);
+        assert!(k(&traded) > k(&fresh));
+    }
+
+    #[test]
+    fn test_locked_liquidity_cannot_be_withdrawn() {
+        let (_, pool) = migrated_pool();
+        assert_eq!(pool.withdraw(pool.lp_supply), Err(TradeError::InsufficientSol));
+        assert_eq!(pool.withdraw(0), Err(TradeError::ZeroAmount));
+        assert_eq!(pool.deposit(0), Err(TradeError::ZeroAmount));
+    }
+}
@@ -11,6 +11,7 @@
 pub const INITIAL_VIRTUAL_SOL_RESERVE: u64   = 30_000_000_000;       // 30 SOL in lamports
 pub const INITIAL_VIRTUAL_TOKEN_RESERVE: u64 = 1_073_000_000_000_000; // 1.073B tokens (6 decimals)
 pub const INITIAL_REAL_TOKEN_RESERVE: u64    = 793_100_000_000_000;   // tokens available for sale
+pub const TOKEN_TOTAL_SUPPLY: u64            = 1_000_000_000_000_000; // 1B tokens — the rest is minted at migration
 pub const GRADUATION_THRESHOLD: u64          = 85_000_000_000;        // 85 SOL in lamports
 pub const PLATFORM_FEE_BPS: u64             = 100;                    // 100 bps = 1%
 pub const BPS_DENOMINATOR: u64              = 10_000;
@@ -40,8 +41,9 @@ pub fn calculate_tokens_out(
     let k = sol_reserve.checked_mul(token_reserve)?;
 
     // new reserves after this trade
+    // Round the new token reserve UP so integer division can never shrink k
     let new_sol_reserve   = sol_reserve.checked_add(sol_amount)?;
-    let new_token_reserve = k.checked_div(new_sol_reserve)?;
+    let new_token_reserve = k.div_ceil(new_sol_reserve);
 
     // tokens buyer receives = how much token reserve shrank
     let tokens_out = token_reserve.checked_sub(new_token_reserve)?;
@@ -70,14 +72,71 @@ pub fn calculate_sol_out(
 
     let k = sol_reserve.checked_mul(token_reserve)?;
 
+    // Round the new SOL reserve UP — the pool keeps the dust, never the seller
     let new_token_reserve = token_reserve.checked_add(token_amount)?;
-    let new_sol_reserve   = k.checked_div(new_token_reserve)?;
+    let new_sol_reserve   = k.div_ceil(new_token_reserve);
 
     let sol_out = sol_reserve.checked_sub(new_sol_reserve)?;
 
     Some(sol_out as u64)
 }
 
+/// Inverse of `calculate_tokens_out`: the smallest SOL amount (after fee)
+/// that buys at least `tokens_out` tokens.
+///
+/// Formula: constant product  →  sol * token = k
+///   new_token_reserve = virtual_token - tokens_out
+///   new_sol_reserve   = ⌈k / new_token_reserve⌉
+///   sol_in            = new_sol_reserve - virtual_sol
+///
+/// Rounds UP — the buyer covers the dust, never the pool.
+pub fn calculate_sol_in(
+    virtual_sol_reserve: u64,
+    virtual_token_reserve: u64,
+    tokens_out: u64,
+) -> Option<u64> {
+    if tokens_out == 0 || tokens_out >= virtual_token_reserve { return None; }
+
+    let sol_reserve   = virtual_sol_reserve as u128;
+    let token_reserve = virtual_token_reserve as u128;
+
+    let k = sol_reserve.checked_mul(token_reserve)?;
+
+    let new_token_reserve = token_reserve.checked_sub(tokens_out as u128)?;
+    let new_sol_reserve   = k.div_ceil(new_token_reserve);
+
+    let sol_in = new_sol_reserve.checked_sub(sol_reserve)?;
+    u64::try_from(sol_in).ok()
+}
+
+/// Inverse of `calculate_sol_out`: the smallest token amount a seller must
+/// return to receive at least `sol_out` lamports (before fee).
+///
+/// Formula: constant product  →  sol * token = k
+///   new_sol_reserve   = virtual_sol - sol_out
+///   new_token_reserve = ⌈k / new_sol_reserve⌉
+///   tokens_in         = new_token_reserve - virtual_token
+///
+/// Rounds UP — the seller covers the dust, never the pool.
+pub fn calculate_tokens_in(
+    virtual_sol_reserve: u64,
+    virtual_token_reserve: u64,
+    sol_out: u64,
+) -> Option<u64> {
+    if sol_out == 0 || sol_out >= virtual_sol_reserve { return None; }
+
+    let sol_reserve   = virtual_sol_reserve as u128;
+    let token_reserve = virtual_token_reserve as u128;
+
+    let k = sol_reserve.checked_mul(token_reserve)?;
+
+    let new_sol_reserve   = sol_reserve.checked_sub(sol_out as u128)?;
+    let new_token_reserve = k.div_ceil(new_sol_reserve);
+
+    let tokens_in = new_token_reserve.checked_sub(token_reserve)?;
+    u64::try_from(tokens_in).ok()
+}
+
 /// Deduct platform fee from SOL amount.
 /// Returns (sol_after_fee, fee_amount)
 pub fn deduct_fee(sol_amount: u64) -> (u64, u64) {
@@ -88,28 +147,974 @@ pub fn deduct_fee(sol_amount: u64) -> (u64, u64) {
     (after_fee, fee)
 }
 
+/// Inverse of the fee deduction: the smallest gross amount that still leaves
+/// `net` after a `fee_bps` fee is taken out (fee = ⌊gross × bps / 10000⌋).
+///
+/// What's left is ⌈gross × (10000 − bps) / 10000⌉, which reaches `net` once
+///   gross = ⌊(net − 1) × 10000 / (10000 − bps)⌋ + 1
+///
+/// Returns None when the fee would eat the whole trade.
+pub fn gross_up_for_fee(net: u64, fee_bps: u64) -> Option<u64> {
+    let kept_bps = BPS_DENOMINATOR.checked_sub(fee_bps).filter(|&b| b > 0)?;
+    if net == 0 { return Some(0); }
+
+    let gross = ((net - 1) as u128)
+        .checked_mul(BPS_DENOMINATOR as u128)?
+        .checked_div(kept_bps as u128)?
+        .checked_add(1)?;
+    u64::try_from(gross).ok()
+}
+
+/// Anti-sniping launch fee, in bps.
+/// Starts at `launch_fee_bps` the moment the token is created and decays
+/// linearly to `base_fee_bps` over `duration` (seconds or slots — the caller
+/// picks the unit, this function only sees `elapsed` and `duration`).
+///
+///   fee = base + (launch - base) * (duration - elapsed) / duration
+///
+/// Rounds UP, so the fee never drops below the schedule early.
+pub fn launch_fee_bps(
+    base_fee_bps: u64,
+    launch_fee_bps: u64,
+    elapsed: u64,
+    duration: u64,
+) -> u64 {
+    // No schedule, or it has already run out
+    if duration == 0 || elapsed >= duration || launch_fee_bps <= base_fee_bps {
+        return base_fee_bps;
+    }
+
+    let spread    = (launch_fee_bps - base_fee_bps) as u128;
+    let remaining = (duration - elapsed) as u128;
+    let decay     = (spread * remaining).div_ceil(duration as u128);
+
+    // decay <= spread, so this always fits back into u64
+    base_fee_bps + decay as u64
+}
+
+/// Split a collected fee between the platform and the token creator.
+/// `creator_share_bps` is a share OF THE FEE, not of the trade.
+/// Returns (platform_fee, creator_fee) — rounding dust goes to the platform.
+pub fn split_fee(fee: u64, creator_share_bps: u64) -> Option<(u64, u64)> {
+    if creator_share_bps > BPS_DENOMINATOR { return None; }
+
+    let creator_fee = (fee as u128)
+        .checked_mul(creator_share_bps as u128)?
+        .checked_div(BPS_DENOMINATOR as u128)? as u64;
+    let platform_fee = fee.checked_sub(creator_fee)?;
+
+    Some((platform_fee, creator_fee))
+}
+
+/// Split a fee three ways when the trade came through a referral link.
+/// Both shares are bps OF THE FEE; the referrer's slice comes out of the
+/// platform's side so the creator earns the same with or without a referrer.
+/// Returns (platform_fee, creator_fee, referral_fee).
+pub fn split_fee_with_referral(
+    fee: u64,
+    creator_share_bps: u64,
+    referral_share_bps: u64,
+) -> Option<(u64, u64, u64)> {
+    if creator_share_bps.checked_add(referral_share_bps)? > BPS_DENOMINATOR { return None; }
+
+    let (platform_fee, creator_fee) = split_fee(fee, creator_share_bps)?;
+    let referral_fee = (fee as u128)
+        .checked_mul(referral_share_bps as u128)?
+        .checked_div(BPS_DENOMINATOR as u128)? as u64;
+    let platform_fee = platform_fee.checked_sub(referral_fee)?;
+
+    Some((platform_fee, creator_fee, referral_fee))
+}
+
+// ─── Price and market cap ─────────────────────────────────────────────────────
+//
+// Integer versions are the source of truth — deterministic, so programs, bots
+// and the indexer all get the same number. Rounding rules:
+//   - price_q64 rounds DOWN to the nearest 2^-64 lamport per base unit
+//   - market caps are computed straight from the reserves, not from the
+//     rounded price, and round DOWN to the lamport
+// The f64 functions below are display wrappers over these.
+
+/// 1.0 in Q64.64 fixed point
+pub const Q64_ONE: u128 = 1 << 64;
+
+/// Spot price in lamports per token base unit, Q64.64:  ⌊virtual_sol × 2^64 / virtual_token⌋
+/// None only when the token reserve is empty.
+pub fn price_q64(virtual_sol_reserve: u64, virtual_token_reserve: u64) -> Option<u128> {
+    // virtual_sol × 2^64 < 2^128, so the shift can't overflow
+    ((virtual_sol_reserve as u128) << 64).checked_div(virtual_token_reserve as u128)
+}
+
+/// A PRICE_SCALE price re-expressed in Q64.64, rounding down.
+/// None once the price no longer fits (2^64 lamports per base unit).
+pub fn scaled_price_to_q64(price: u128) -> Option<u128> {
+    mul_div_floor(price, Q64_ONE, PRICE_SCALE)
+}
+
+/// Lamport value of `supply` tokens at the spot price:  ⌊virtual_sol × supply / virtual_token⌋
+pub fn market_cap_lamports(
+    virtual_sol_reserve: u64,
+    virtual_token_reserve: u64,
+    supply: u64,
+) -> Option<u64> {
+    let cap = (virtual_sol_reserve as u128)
+        .checked_mul(supply as u128)?
+        .checked_div(virtual_token_reserve as u128)?;
+    u64::try_from(cap).ok()
+}
+
+/// Lamport value of `supply` tokens at the curve's spot price:  ⌊price_q64 × supply / 2^64⌋
+/// Goes through the shape's own price, so it holds for every `CurveKind` —
+/// only constant product prices off the virtual reserves.
+pub fn market_cap_at_spot(curve: &impl PricingCurve, state: &CurveState, supply: u64) -> Option<u64> {
+    let cap = mul_div_floor(curve.spot_price_q64(state)?, supply as u128, Q64_ONE)?;
+    u64::try_from(cap).ok()
+}
+
+/// Market cap of the full token supply — sold, still on the curve, and the
+/// allocation minted into the pool at migration
+pub fn fully_diluted_market_cap(curve: &impl PricingCurve, state: &CurveState) -> Option<u64> {
+    market_cap_at_spot(curve, state, TOKEN_TOTAL_SUPPLY)
+}
+
+/// Market cap of the tokens in holders' hands (tokens sold)
+pub fn circulating_market_cap(curve: &impl PricingCurve, state: &CurveState) -> Option<u64> {
+    market_cap_at_spot(curve, state, state.tokens_sold)
+}
+
 /// Current price of 1 token in SOL (as f64, for display only — never use f64 on-chain)
 pub fn current_price_per_token(
     virtual_sol_reserve: u64,
     virtual_token_reserve: u64,
 ) -> f64 {
-    // Price = SOL reserve / token reserve
-    // Both in their base units (lamports and 6-decimal tokens)
-    // Normalize: sol in SOL (÷1e9), tokens in whole tokens (÷1e6)
-    let sol_reserve_sol     = virtual_sol_reserve as f64 / 1e9;
-    let token_reserve_whole = virtual_token_reserve as f64 / 1e6;
-    sol_reserve_sol / token_reserve_whole
+    // lamports per base unit → SOL per whole token: × 1e6 / 1e9
+    let Some(price) = price_q64(virtual_sol_reserve, virtual_token_reserve) else { return 0.0 };
+    price as f64 / Q64_ONE as f64 / 1e3
 }
 
-/// Market cap in SOL at current price (for graduation progress display)
+/// Market cap in SOL of `supply` tokens at the current price (for display only).
+/// Pass `TOKEN_TOTAL_SUPPLY` for fully diluted or `tokens_sold` for circulating.
 pub fn market_cap_sol(
     virtual_sol_reserve: u64,
     virtual_token_reserve: u64,
-    total_supply: u64,
+    supply: u64,
 ) -> f64 {
-    let price = current_price_per_token(virtual_sol_reserve, virtual_token_reserve);
-    let supply_whole = total_supply as f64 / 1e6;
-    price * supply_whole
+    market_cap_lamports(virtual_sol_reserve, virtual_token_reserve, supply)
+        .map_or(0.0, |cap| cap as f64 / 1e9)
+}
+
+// ─── Curve shapes ─────────────────────────────────────────────────────────────
+//
+// Every shape answers the same two questions: how many tokens does `sol_in`
+// buy, and how much SOL does selling `tokens_in` return. Constant product uses
+// the closed-form functions above. The other shapes are defined by a price
+// function p(s) over tokens sold `s`, and priced through its integral
+//
+//   F(s) = ∫₀ˢ p(x) dx      (lamports × PRICE_SCALE)
+//
+// A buy of Δ at s costs  ⌈(F(s+Δ) − F(s)) / PRICE_SCALE⌉
+// A sell of Δ at s pays  ⌊(F(s) − F(s−Δ)) / PRICE_SCALE⌋
+//
+// Both sides price against the same F, rounding in the pool's favour, so no
+// sequence of trades that ends where it started can pay out more than it took in.
+
+/// Shape prices are lamports per token base unit, scaled up by this factor.
+/// The default constant-product launch price is ~27_958_993 in these units.
+pub const PRICE_SCALE: u128 = 1_000_000_000_000;
+
+/// ln(2) in Q48 fixed point
+const LN2_Q48: u128 = 195_103_586_505_167;
+const Q48_ONE: u128 = 1 << 48;
+
+/// Reserves of a bonding curve — mirrors the on-chain BondingCurve fields
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CurveState {
+    pub virtual_sol_reserve: u64,
+    pub virtual_token_reserve: u64,
+    pub real_sol_balance: u64,
+    pub real_token_reserve: u64,
+    /// Tokens sold so far (BondingCurve.token_total_supply)
+    pub tokens_sold: u64,
+}
+
+impl CurveState {
+    /// Fresh curve with the default launch reserves
+    pub fn initial() -> Self {
+        CurveState {
+            virtual_sol_reserve: INITIAL_VIRTUAL_SOL_RESERVE,
+            virtual_token_reserve: INITIAL_VIRTUAL_TOKEN_RESERVE,
+            real_sol_balance: 0,
+            real_token_reserve: INITIAL_REAL_TOKEN_RESERVE,
+            tokens_sold: 0,
+        }
+    }
+
+    /// Total tokens the curve sells before it completes
+    pub fn curve_supply(&self) -> u64 {
+        self.tokens_sold.saturating_add(self.real_token_reserve)
+    }
+}
+
+/// Common interface every curve shape implements
+pub trait PricingCurve {
+    /// Tokens received for `sol_in` lamports (fee already deducted)
+    fn tokens_out(&self, state: &CurveState, sol_in: u64) -> Option<u64>;
+
+    /// Lamports returned for selling `tokens_in` (before fee)
+    fn sol_out(&self, state: &CurveState, tokens_in: u64) -> Option<u64>;
+
+    /// Lamports (fee already deducted) needed to buy exactly `tokens_out` —
+    /// rounds UP, so `tokens_out(sol_in(n)) >= n`
+    fn sol_in(&self, state: &CurveState, tokens_out: u64) -> Option<u64>;
+
+    /// Tokens a seller must return to receive `sol_out` lamports (before fee) —
+    /// rounds UP, so `sol_out(tokens_in(n)) >= n`
+    fn tokens_in(&self, state: &CurveState, sol_out: u64) -> Option<u64>;
+
+    /// Marginal price of the next token, in PRICE_SCALE units
+    fn spot_price(&self, state: &CurveState) -> Option<u128>;
+
+    /// Marginal price of the next token in Q64.64 lamports per base unit —
+    /// what the TWAP accumulates. Shapes that price natively in Q64.64 override it.
+    fn spot_price_q64(&self, state: &CurveState) -> Option<u128> {
+        scaled_price_to_q64(self.spot_price(state)?)
+    }
+
+    /// Whether the shape's parameters describe a usable curve
+    fn is_valid(&self) -> bool { true }
+}
+
+/// virtual_sol × virtual_token = k
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConstantProduct;
+
+impl PricingCurve for ConstantProduct {
+    fn tokens_out(&self, state: &CurveState, sol_in: u64) -> Option<u64> {
+        calculate_tokens_out(state.virtual_sol_reserve, state.virtual_token_reserve, sol_in)
+    }
+
+    fn sol_out(&self, state: &CurveState, tokens_in: u64) -> Option<u64> {
+        calculate_sol_out(state.virtual_sol_reserve, state.virtual_token_reserve, tokens_in)
+    }
+
+    fn sol_in(&self, state: &CurveState, tokens_out: u64) -> Option<u64> {
+        calculate_sol_in(state.virtual_sol_reserve, state.virtual_token_reserve, tokens_out)
+    }
+
+    fn tokens_in(&self, state: &CurveState, sol_out: u64) -> Option<u64> {
+        calculate_tokens_in(state.virtual_sol_reserve, state.virtual_token_reserve, sol_out)
+    }
+
+    fn spot_price(&self, state: &CurveState) -> Option<u128> {
+        // virtual_sol / virtual_token
+        (state.virtual_sol_reserve as u128)
+            .checked_mul(PRICE_SCALE)?
+            .checked_div(state.virtual_token_reserve as u128)
+    }
+
+    fn spot_price_q64(&self, state: &CurveState) -> Option<u128> {
+        price_q64(state.virtual_sol_reserve, state.virtual_token_reserve)
+    }
+}
+
+/// Price rises in a straight line from `start_price` (nothing sold)
+/// to `end_price` (whole curve supply sold).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Linear {
+    pub start_price: u64,
+    pub end_price: u64,
+}
+
+/// Price doubles every `doubling_tokens` sold: p(s) = start_price × 2^(s / doubling_tokens)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Exponential {
+    pub start_price: u64,
+    pub doubling_tokens: u64,
+}
+
+/// Flat price within each step of `step_tokens`; every step costs
+/// `step_increment` more than the one before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stepped {
+    pub start_price: u64,
+    pub step_tokens: u64,
+    pub step_increment: u64,
+}
+
+/// Shapes priced through the integral F(s) of their price function
+trait IntegralCurve {
+    /// F(s) in lamports × PRICE_SCALE. Must never decrease as `s` grows.
+    fn cumulative_cost(&self, state: &CurveState, tokens_sold: u64) -> Option<u128>;
+}
+
+impl IntegralCurve for Linear {
+    fn cumulative_cost(&self, state: &CurveState, s: u64) -> Option<u128> {
+        // F(s) = start × s + (end − start) × s² / (2 × supply)
+        let supply = (state.curve_supply() as u128).checked_mul(2)?;
+        if supply == 0 { return None; }
+
+        let s     = s as u128;
+        let rise  = (self.end_price as u128).checked_sub(self.start_price as u128)?;
+        let base  = (self.start_price as u128).checked_mul(s)?;
+        let ramp  = mul_div_floor(s.checked_mul(s)?, rise, supply)?;
+        base.checked_add(ramp)
+    }
+}
+
+impl IntegralCurve for Exponential {
+    fn cumulative_cost(&self, _state: &CurveState, s: u64) -> Option<u128> {
+        // F(s) = start × H × (2^(s/H) − 1) / ln 2
+        let h = self.doubling_tokens as u128;
+        if h == 0 { return None; }
+
+        let s = s as u128;
+        let growth = exp2_q48(s / h, ((s % h) << 48) / h)?.checked_sub(Q48_ONE)?;
+
+        (self.start_price as u128)
+            .checked_mul(h)?
+            .checked_mul(growth)?
+            .checked_div(LN2_Q48)
+    }
+}
+
+impl IntegralCurve for Stepped {
+    fn cumulative_cost(&self, _state: &CurveState, s: u64) -> Option<u128> {
+        // n full steps at start, start + inc, …, plus the partial step
+        let step = self.step_tokens as u128;
+        if step == 0 { return None; }
+
+        let s     = s as u128;
+        let n     = s / step;
+        let rem   = s % step;
+        let start = self.start_price as u128;
+        let inc   = self.step_increment as u128;
+
+        let full_steps = n
+            .checked_mul(start)?
+            .checked_add(inc.checked_mul(n.checked_mul(n.saturating_sub(1))? / 2)?)?
+            .checked_mul(step)?;
+        let partial = rem.checked_mul(start.checked_add(inc.checked_mul(n)?)?)?;
+        full_steps.checked_add(partial)
+    }
+}
+
+/// Cost in lamports of buying `amount` tokens at `tokens_sold` — rounds UP
+fn integral_buy_cost<C: IntegralCurve>(curve: &C, state: &CurveState, amount: u64) -> Option<u64> {
+    let from = curve.cumulative_cost(state, state.tokens_sold)?;
+    let to   = curve.cumulative_cost(state, state.tokens_sold.checked_add(amount)?)?;
+    let cost = to.checked_sub(from)?.div_ceil(PRICE_SCALE);
+    u64::try_from(cost).ok()
+}
+
+/// Largest token amount whose buy cost fits in `sol_in`, up to the tokens left
+/// on the curve. F is non-decreasing, so cost is too and a binary search finds
+/// the edge in at most log2(real_token_reserve) steps.
+fn integral_tokens_out<C: IntegralCurve>(curve: &C, state: &CurveState, sol_in: u64) -> Option<u64> {
+    if sol_in == 0 { return None; }
+
+    let affordable = |amount: u64| {
+        integral_buy_cost(curve, state, amount).is_some_and(|cost| cost <= sol_in)
+    };
+
+    // The curve can't sell past its reserve, so there is nothing to search beyond it
+    let (mut lo, mut hi) = (0u64, state.real_token_reserve);
+    while lo < hi {
+        let mid = lo + (hi - lo).div_ceil(2);
+        if affordable(mid) { lo = mid; } else { hi = mid - 1; }
+    }
+    Some(lo)
+}
+
+/// Lamports paid for selling `tokens_in` back at `tokens_sold` — rounds DOWN
+fn integral_sol_out<C: IntegralCurve>(curve: &C, state: &CurveState, tokens_in: u64) -> Option<u64> {
+    if tokens_in == 0 { return None; }
+
+    let to     = curve.cumulative_cost(state, state.tokens_sold)?;
+    let from   = curve.cumulative_cost(state, state.tokens_sold.checked_sub(tokens_in)?)?;
+    let payout = to.checked_sub(from)? / PRICE_SCALE;
+    u64::try_from(payout).ok()
+}
+
+/// Smallest token amount whose sell pays at least `sol_out`.
+/// Payout only grows with the amount sold, so a binary search finds the edge.
+fn integral_tokens_in<C: IntegralCurve>(curve: &C, state: &CurveState, sol_out: u64) -> Option<u64> {
+    if sol_out == 0 { return None; }
+
+    let enough = |amount: u64| {
+        integral_sol_out(curve, state, amount).is_some_and(|payout| payout >= sol_out)
+    };
+
+    // Selling every token sold so far is the most the curve can pay out
+    if !enough(state.tokens_sold) { return None; }
+
+    let (mut lo, mut hi) = (1u64, state.tokens_sold);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if enough(mid) { hi = mid; } else { lo = mid + 1; }
+    }
+    Some(lo)
+}
+
+impl PricingCurve for Linear {
+    fn tokens_out(&self, state: &CurveState, sol_in: u64) -> Option<u64> {
+        integral_tokens_out(self, state, sol_in)
+    }
+
+    fn sol_out(&self, state: &CurveState, tokens_in: u64) -> Option<u64> {
+        integral_sol_out(self, state, tokens_in)
+    }
+
+    fn sol_in(&self, state: &CurveState, tokens_out: u64) -> Option<u64> {
+        if tokens_out == 0 { return None; }
+        integral_buy_cost(self, state, tokens_out)
+    }
+
+    fn tokens_in(&self, state: &CurveState, sol_out: u64) -> Option<u64> {
+        integral_tokens_in(self, state, sol_out)
+    }
+
+    fn spot_price(&self, state: &CurveState) -> Option<u128> {
+        // p(s) = start + (end − start) × s / supply
+        let supply = state.curve_supply() as u128;
+        if supply == 0 { return None; }
+        let rise = (self.end_price as u128).checked_sub(self.start_price as u128)?;
+        let ramp = mul_div_floor(state.tokens_sold as u128, rise, supply)?;
+        (self.start_price as u128).checked_add(ramp)
+    }
+
+    fn is_valid(&self) -> bool {
+        self.start_price > 0 && self.end_price >= self.start_price && prices_full_supply(self)
+    }
+}
+
+impl PricingCurve for Exponential {
+    fn tokens_out(&self, state: &CurveState, sol_in: u64) -> Option<u64> {
+        integral_tokens_out(self, state, sol_in)
+    }
+
+    fn sol_out(&self, state: &CurveState, tokens_in: u64) -> Option<u64> {
+        integral_sol_out(self, state, tokens_in)
+    }
+
+    fn sol_in(&self, state: &CurveState, tokens_out: u64) -> Option<u64> {
+        if tokens_out == 0 { return None; }
+        integral_buy_cost(self, state, tokens_out)
+    }
+
+    fn tokens_in(&self, state: &CurveState, sol_out: u64) -> Option<u64> {
+        integral_tokens_in(self, state, sol_out)
+    }
+
+    fn spot_price(&self, state: &CurveState) -> Option<u128> {
+        // p(s) = start × 2^(s / H)
+        let h = self.doubling_tokens as u128;
+        if h == 0 { return None; }
+        let s = state.tokens_sold as u128;
+        let growth = exp2_q48(s / h, ((s % h) << 48) / h)?;
+        Some((self.start_price as u128).checked_mul(growth)? >> 48)
+    }
+
+    fn is_valid(&self) -> bool {
+        self.start_price > 0 && self.doubling_tokens > 0 && prices_full_supply(self)
+    }
+}
+
+impl PricingCurve for Stepped {
+    fn tokens_out(&self, state: &CurveState, sol_in: u64) -> Option<u64> {
+        integral_tokens_out(self, state, sol_in)
+    }
+
+    fn sol_out(&self, state: &CurveState, tokens_in: u64) -> Option<u64> {
+        integral_sol_out(self, state, tokens_in)
+    }
+
+    fn sol_in(&self, state: &CurveState, tokens_out: u64) -> Option<u64> {
+        if tokens_out == 0 { return None; }
+        integral_buy_cost(self, state, tokens_out)
+    }
+
+    fn tokens_in(&self, state: &CurveState, sol_out: u64) -> Option<u64> {
+        integral_tokens_in(self, state, sol_out)
+    }
+
+    fn spot_price(&self, state: &CurveState) -> Option<u128> {
+        // Price of the step the next token falls in
+        let step = self.step_tokens as u128;
+        if step == 0 { return None; }
+        let n = state.tokens_sold as u128 / step;
+        (self.start_price as u128).checked_add((self.step_increment as u128).checked_mul(n)?)
+    }
+
+    fn is_valid(&self) -> bool {
+        self.start_price > 0 && self.step_tokens > 0 && prices_full_supply(self)
+    }
+}
+
+/// Whether an integral shape can sell out the default launch: the cost of the
+/// whole INITIAL_REAL_TOKEN_RESERVE fits in u64 lamports and the last token
+/// still has a Q64.64 spot price. A shape that can't is accepted at launch
+/// but never completes — every buy past some point overflows.
+fn prices_full_supply<C: IntegralCurve + PricingCurve>(curve: &C) -> bool {
+    let launch = CurveState::initial();
+    let sold_out = CurveState { tokens_sold: INITIAL_REAL_TOKEN_RESERVE, real_token_reserve: 0, ..launch };
+    let total_cost = curve
+        .cumulative_cost(&launch, INITIAL_REAL_TOKEN_RESERVE)
+        .and_then(|cost| u64::try_from(cost.div_ceil(PRICE_SCALE)).ok());
+    total_cost.is_some() && curve.spot_price_q64(&sold_out).is_some()
+}
+
+/// ⌊a × b / d⌋ without forming a × b when a is the large factor
+fn mul_div_floor(a: u128, b: u128, d: u128) -> Option<u128> {
+    let whole = (a / d).checked_mul(b)?;
+    let frac  = (a % d).checked_mul(b)? / d;
+    whole.checked_add(frac)
+}
+
+/// 2^(int + frac / 2^48) in Q48. `frac` must be below 2^48.
+/// Fractional part via the Taylor series of e^(frac × ln 2); every term
+/// is non-decreasing in `frac`, so the result is too.
+fn exp2_q48(int: u128, frac: u128) -> Option<u128> {
+    if int > 64 { return None; }
+
+    let x = frac.checked_mul(LN2_Q48)? >> 48;
+    let mut term = Q48_ONE;
+    let mut sum  = Q48_ONE;
+    for n in 1..=24u128 {
+        term = ((term * x) >> 48) / n;
+        if term == 0 { break; }
+        sum += term;
+    }
+    sum.checked_shl(int as u32).filter(|v| v >> int == sum)
+}
+
+// ─── Curve completion ─────────────────────────────────────────────────────────
+
+/// Fills a buy of `sol_in` lamports (fee already deducted), capped at the
+/// point where the curve completes: either the SOL raised reaches
+/// `grad_threshold` or the real token reserve runs out.
+///
+/// Returns (sol_used, tokens_out). When the buy overshoots, `sol_used` is
+/// only what the capped tokens cost and the caller refunds the rest.
+/// CurveComplete only when the curve already is; nothing to spend is
+/// ZeroAmount and a pricing failure is MathOverflow.
+pub fn capped_buy<C: PricingCurve + ?Sized>(
+    curve: &C,
+    state: &CurveState,
+    sol_in: u64,
+    grad_threshold: u64,
+) -> Result<(u64, u64), TradeError> {
+    // SOL the curve can still take before it reaches the threshold
+    let sol_room = grad_threshold.saturating_sub(state.real_sol_balance);
+    if sol_room == 0 || state.real_token_reserve == 0 { return Err(TradeError::CurveComplete); }
+    if sol_in == 0 { return Err(TradeError::ZeroAmount); }
+
+    let sol_used   = sol_in.min(sol_room);
+    let tokens_out = curve.tokens_out(state, sol_used).ok_or(TradeError::MathOverflow("tokens_out"))?;
+    if tokens_out < state.real_token_reserve {
+        return Ok((sol_used, tokens_out));
+    }
+
+    // Sells out the reserve — charge only what the last tokens cost. Integral
+    // shapes stop at the reserve, so this also runs when they land exactly on it.
+    // sol_in rounds up, but never past sol_used since sol_used already buys them all.
+    let tokens_out = state.real_token_reserve;
+    let sol_used   = curve.sol_in(state, tokens_out).ok_or(TradeError::MathOverflow("sol_in"))?.min(sol_used);
+    Ok((sol_used, tokens_out))
+}
+
+/// The `min_tokens_out` a buy is held to once filled. A capped fill spends
+/// only `sol_used` of the `sol_offered` (both fee excluded) the floor was
+/// quoted for, so the floor scales down with it — slippage stays a bound on
+/// the price paid, not on how much of the buy filled. Rounds UP.
+pub fn min_tokens_for_fill(min_tokens_out: u64, sol_offered: u64, sol_used: u64) -> u64 {
+    if sol_used >= sol_offered { return min_tokens_out; }
+    // sol_used < sol_offered, so the result is below min_tokens_out and fits
+    (min_tokens_out as u128 * sol_used as u128).div_ceil(sol_offered as u128) as u64
+}
+
+// ─── Trade execution ──────────────────────────────────────────────────────────
+//
+// The on-chain buy / sell instructions price every trade through these
+// functions and write back the reserves they return, so this module is the
+// only place the numbers are decided.
+
+/// Why a trade could not be priced. lib.rs maps each case to an ErrorCode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TradeError {
+    /// The trade moves no tokens or no SOL
+    ZeroAmount,
+    /// A reserve or amount would overflow / underflow — names the value,
+    /// which lib.rs logs next to the error
+    MathOverflow(&'static str),
+    /// Fee above 100% (or exactly 100% where the trade needs something left)
+    InvalidFeeBps,
+    /// Not enough tokens left on the curve
+    InsufficientTokens,
+    /// Not enough SOL raised to pay the seller
+    InsufficientSol,
+    /// The curve has already completed and takes no more buys
+    CurveComplete,
+}
+
+/// Everything a priced trade changes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TradeOutcome {
+    /// Tokens the trader receives (buy) or returns (sell)
+    pub token_amount: u64,
+    /// Lamports the trader pays in (buy) or that leave the curve (sell), fee included
+    pub sol_amount: u64,
+    /// Fee taken out of `sol_amount`
+    pub fee: u64,
+    /// `sol_amount` minus the fee — what reaches the curve (buy) or the seller (sell)
+    pub sol_net: u64,
+    /// Curve reserves after the trade
+    pub state: CurveState,
+}
+
+impl CurveState {
+    /// Reserves after `sol_in` (after fee) buys `tokens_out`
+    pub fn after_buy(&self, sol_in: u64, tokens_out: u64) -> Result<CurveState, TradeError> {
+        Ok(CurveState {
+            virtual_sol_reserve: self.virtual_sol_reserve.checked_add(sol_in).ok_or(TradeError::MathOverflow("virtual_sol_reserve"))?,
+            virtual_token_reserve: self.virtual_token_reserve.checked_sub(tokens_out).ok_or(TradeError::MathOverflow("virtual_token_reserve"))?,
+            real_sol_balance: self.real_sol_balance.checked_add(sol_in).ok_or(TradeError::MathOverflow("real_sol_balance"))?,
+            real_token_reserve: self.real_token_reserve.checked_sub(tokens_out).ok_or(TradeError::MathOverflow("real_token_reserve"))?,
+            tokens_sold: self.tokens_sold.checked_add(tokens_out).ok_or(TradeError::MathOverflow("tokens_sold"))?,
+        })
+    }
+
+    /// Reserves after selling `tokens_in` for `sol_out` (before fee)
+    pub fn after_sell(&self, tokens_in: u64, sol_out: u64) -> Result<CurveState, TradeError> {
+        Ok(CurveState {
+            virtual_sol_reserve: self.virtual_sol_reserve.checked_sub(sol_out).ok_or(TradeError::MathOverflow("virtual_sol_reserve"))?,
+            virtual_token_reserve: self.virtual_token_reserve.checked_add(tokens_in).ok_or(TradeError::MathOverflow("virtual_token_reserve"))?,
+            real_sol_balance: self.real_sol_balance.checked_sub(sol_out).ok_or(TradeError::MathOverflow("real_sol_balance"))?,
+            real_token_reserve: self.real_token_reserve.checked_add(tokens_in).ok_or(TradeError::MathOverflow("real_token_reserve"))?,
+            tokens_sold: self.tokens_sold.checked_sub(tokens_in).ok_or(TradeError::MathOverflow("tokens_sold"))?,
+        })
+    }
+}
+
+/// ⌊amount × fee_bps / 10000⌋ in u128, so large amounts cannot overflow.
+/// A fee above 100% is rejected rather than producing a fee bigger than the trade.
+pub fn fee_for(amount: u64, fee_bps: u64) -> Result<u64, TradeError> {
+    if fee_bps > BPS_DENOMINATOR { return Err(TradeError::InvalidFeeBps); }
+
+    // fee_bps ≤ 10000, so the fee is at most `amount` and always fits
+    Ok((amount as u128 * fee_bps as u128 / BPS_DENOMINATOR as u128) as u64)
+}
+
+/// Buy with `sol_amount` lamports, fee included. The buy that completes the
+/// curve is capped (see `capped_buy`) and only charged for the filled part.
+pub fn execute_buy<C: PricingCurve + ?Sized>(
+    curve: &C,
+    state: &CurveState,
+    sol_amount: u64,
+    fee_bps: u64,
+    grad_threshold: u64,
+) -> Result<TradeOutcome, TradeError> {
+    if sol_amount == 0 { return Err(TradeError::ZeroAmount); }
+
+    let fee     = fee_for(sol_amount, fee_bps)?;
+    let sol_net = sol_amount - fee;
+
+    let (sol_used, tokens_out) = capped_buy(curve, state, sol_net, grad_threshold)?;
+    if tokens_out == 0 { return Err(TradeError::ZeroAmount); }
+
+    // Charge only for the filled part; the rest never leaves the buyer
+    let (sol_amount, fee) = if sol_used < sol_net {
+        let charged = gross_up_for_fee(sol_used, fee_bps).ok_or(TradeError::InvalidFeeBps)?;
+        (charged, charged - sol_used)
+    } else {
+        (sol_amount, fee)
+    };
+
+    Ok(TradeOutcome {
+        token_amount: tokens_out,
+        sol_amount,
+        fee,
+        sol_net: sol_used,
+        state: state.after_buy(sol_used, tokens_out)?,
+    })
+}
+
+/// Buy exactly `token_amount` tokens. Both the curve cost and the fee round
+/// up, so the pool is never short for the tokens it sends.
+pub fn execute_buy_exact_tokens<C: PricingCurve + ?Sized>(
+    curve: &C,
+    state: &CurveState,
+    token_amount: u64,
+    fee_bps: u64,
+) -> Result<TradeOutcome, TradeError> {
+    if token_amount == 0 { return Err(TradeError::ZeroAmount); }
+    if token_amount > state.real_token_reserve { return Err(TradeError::InsufficientTokens); }
+
+    let sol_net    = curve.sol_in(state, token_amount).ok_or(TradeError::InsufficientTokens)?;
+    let sol_amount = gross_up_for_fee(sol_net, fee_bps).ok_or(TradeError::InvalidFeeBps)?;
+
+    Ok(TradeOutcome {
+        token_amount,
+        sol_amount,
+        fee: sol_amount - sol_net,
+        sol_net,
+        state: state.after_buy(sol_net, token_amount)?,
+    })
+}
+
+/// Sell `token_amount` tokens back to the curve
+pub fn execute_sell<C: PricingCurve + ?Sized>(
+    curve: &C,
+    state: &CurveState,
+    token_amount: u64,
+    fee_bps: u64,
+) -> Result<TradeOutcome, TradeError> {
+    if token_amount == 0 { return Err(TradeError::ZeroAmount); }
+
+    let sol_out = curve.sol_out(state, token_amount).unwrap_or(0);
+    if sol_out == 0 { return Err(TradeError::ZeroAmount); }
+    if sol_out > state.real_sol_balance { return Err(TradeError::InsufficientSol); }
+
+    let fee = fee_for(sol_out, fee_bps)?;
+
+    Ok(TradeOutcome {
+        token_amount,
+        sol_amount: sol_out,
+        fee,
+        sol_net: sol_out - fee,
+        state: state.after_sell(token_amount, sol_out)?,
+    })
+}
+
+/// Sell just enough tokens for the seller to net exactly `sol_out` after the
+/// fee. Rounds up — any surplus the curve would pay for those tokens stays in
+/// the pool.
+pub fn execute_sell_for_exact_sol<C: PricingCurve + ?Sized>(
+    curve: &C,
+    state: &CurveState,
+    sol_out: u64,
+    fee_bps: u64,
+) -> Result<TradeOutcome, TradeError> {
+    if sol_out == 0 { return Err(TradeError::ZeroAmount); }
+
+    // SOL leaving the curve, fee included
+    let gross = gross_up_for_fee(sol_out, fee_bps).ok_or(TradeError::InvalidFeeBps)?;
+    if gross > state.real_sol_balance { return Err(TradeError::InsufficientSol); }
+
+    let token_amount = curve.tokens_in(state, gross).ok_or(TradeError::InsufficientSol)?;
+
+    Ok(TradeOutcome {
+        token_amount,
+        sol_amount: gross,
+        fee: gross - sol_out,
+        sol_net: sol_out,
+        state: state.after_sell(token_amount, gross)?,
+    })
+}
+
+// ─── Quotes ───────────────────────────────────────────────────────────────────
+//
+// What a trade would do, priced exactly like the instruction would price it,
+// for frontends to display. Prices are lamports per token base unit × PRICE_SCALE.
+
+/// Everything a client needs to show before submitting a trade
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quote {
+    /// Tokens received (buy) or sold (sell)
+    pub token_amount: u64,
+    /// Buy: lamports the buyer pays, fee included. Sell: lamports leaving the curve, before fee.
+    pub sol_gross: u64,
+    /// Buy: lamports reaching the curve. Sell: lamports the seller receives.
+    pub sol_net: u64,
+    pub fee: u64,
+    /// Lamports per token the trader actually pays / receives, fee included
+    pub avg_price: u128,
+    pub spot_price_before: u128,
+    pub spot_price_after: u128,
+    /// How far the fill price (fee excluded) is from the spot price before the trade
+    pub price_impact_bps: u64,
+    /// Curve reserves after the trade
+    pub state_after: CurveState,
+}
+
+/// Quote a buy of `sol_amount` lamports, fee included — same pricing as `execute_buy`
+pub fn quote_buy<C: PricingCurve + ?Sized>(
+    curve: &C,
+    state: &CurveState,
+    sol_amount: u64,
+    fee_bps: u64,
+    grad_threshold: u64,
+) -> Result<Quote, TradeError> {
+    let outcome = execute_buy(curve, state, sol_amount, fee_bps, grad_threshold)?;
+    build_quote(curve, state, &outcome, outcome.sol_amount, outcome.sol_net)
+}
+
+/// Quote a sell of `token_amount` tokens — same pricing as `execute_sell`
+pub fn quote_sell<C: PricingCurve + ?Sized>(
+    curve: &C,
+    state: &CurveState,
+    token_amount: u64,
+    fee_bps: u64,
+) -> Result<Quote, TradeError> {
+    let outcome = execute_sell(curve, state, token_amount, fee_bps)?;
+    build_quote(curve, state, &outcome, outcome.sol_net, outcome.sol_amount)
+}
+
+/// `trader_sol` is what the trader pays / receives (fee included);
+/// `curve_sol` is what the curve takes / pays (fee excluded)
+fn build_quote<C: PricingCurve + ?Sized>(
+    curve: &C,
+    state: &CurveState,
+    outcome: &TradeOutcome,
+    trader_sol: u64,
+    curve_sol: u64,
+) -> Result<Quote, TradeError> {
+    let tokens = outcome.token_amount as u128;
+    let spot_price_before = curve.spot_price(state).ok_or(TradeError::MathOverflow("spot_price_before"))?;
+    let spot_price_after  = curve.spot_price(&outcome.state).ok_or(TradeError::MathOverflow("spot_price_after"))?;
+
+    let per_token = |sol: u64| {
+        (sol as u128).checked_mul(PRICE_SCALE).map(|v| v / tokens).ok_or(TradeError::MathOverflow("avg_price"))
+    };
+    let avg_price  = per_token(trader_sol)?;
+    let fill_price = per_token(curve_sol)?;
+
+    let price_impact_bps = if spot_price_before == 0 {
+        0
+    } else {
+        let gap = fill_price.abs_diff(spot_price_before);
+        let bps = gap.checked_mul(BPS_DENOMINATOR as u128).ok_or(TradeError::MathOverflow("price_impact_bps"))? / spot_price_before;
+        u64::try_from(bps).unwrap_or(u64::MAX)
+    };
+
+    Ok(Quote {
+        token_amount: outcome.token_amount,
+        sol_gross: outcome.sol_amount,
+        sol_net: outcome.sol_net,
+        fee: outcome.fee,
+        avg_price,
+        spot_price_before,
+        spot_price_after,
+        price_impact_bps,
+        state_after: outcome.state,
+    })
+}
+
+// ─── Graduation planning ──────────────────────────────────────────────────────
+//
+// Answers for bots deciding how to size a trade. Each mirrors what the
+// instructions would actually do with the same state, so acting on the answer
+// lands exactly where it says.
+
+/// Lamports, fee included, that a single buy needs to complete the curve —
+/// whichever comes first of the SOL threshold and selling out the reserve.
+/// Zero once the curve is complete.
+pub fn sol_needed_to_graduate<C: PricingCurve + ?Sized>(
+    curve: &C,
+    state: &CurveState,
+    grad_threshold: u64,
+    fee_bps: u64,
+) -> Option<u64> {
+    let sol_room = grad_threshold.saturating_sub(state.real_sol_balance);
+    if sol_room == 0 || state.real_token_reserve == 0 { return Some(0); }
+
+    // What the rest of the reserve costs, if that's cheaper than the threshold
+    let sell_out = curve.sol_in(state, state.real_token_reserve).unwrap_or(u64::MAX);
+    gross_up_for_fee(sol_room.min(sell_out), fee_bps)
+}
+
+/// Most tokens that can still be bought before the curve completes
+pub fn tokens_remaining<C: PricingCurve + ?Sized>(
+    curve: &C,
+    state: &CurveState,
+    grad_threshold: u64,
+) -> Option<u64> {
+    if state.real_sol_balance >= grad_threshold || state.real_token_reserve == 0 { return Some(0); }
+
+    capped_buy(curve, state, u64::MAX, grad_threshold).ok().map(|(_, tokens_out)| tokens_out)
+}
+
+/// How far the curve is towards completing, 0..=10000. Rounds down, so
+/// 10000 means complete and nothing short of it.
+pub fn graduation_progress_bps(state: &CurveState, grad_threshold: u64) -> u64 {
+    if state.real_sol_balance >= grad_threshold || state.real_token_reserve == 0 {
+        return BPS_DENOMINATOR;
+    }
+    // real_sol_balance < grad_threshold here, so the result is below 10000
+    (state.real_sol_balance as u128 * BPS_DENOMINATOR as u128 / grad_threshold as u128) as u64
+}
+
+/// Lamports, fee included, that `buy_exact_tokens` charges for `tokens_out`
+pub fn sol_in_for_tokens_out<C: PricingCurve + ?Sized>(
+    curve: &C,
+    state: &CurveState,
+    tokens_out: u64,
+    fee_bps: u64,
+) -> Option<u64> {
+    execute_buy_exact_tokens(curve, state, tokens_out, fee_bps).ok().map(|outcome| outcome.sol_amount)
+}
+
+/// Tokens `sell_for_exact_sol` takes for the seller to net `sol_out`
+pub fn tokens_in_for_sol_out<C: PricingCurve + ?Sized>(
+    curve: &C,
+    state: &CurveState,
+    sol_out: u64,
+    fee_bps: u64,
+) -> Option<u64> {
+    execute_sell_for_exact_sol(curve, state, sol_out, fee_bps).ok().map(|outcome| outcome.token_amount)
+}
+
+// ─── TWAP ─────────────────────────────────────────────────────────────────────
+//
+// BondingCurve keeps Σ price × seconds, with the price in Q64.64 lamports per
+// base unit and the sum wrapping on overflow, as in Uniswap v2. A trade first accrues the price that
+// held since the previous trade, then moves the reserves, so a price pushed up
+// and straight back down inside one second never accrues at all, and one held
+// for a second counts for exactly one second of the window.
+
+/// Price accrued when a shape can't state its spot price in Q64.64 — an empty
+/// token reserve, or a price past 2^64 lamports per base unit. No base unit can
+/// settle above u64::MAX lamports, so the TWAP saturates there rather than the
+/// trade failing.
+pub const MAX_SPOT_PRICE_Q64: u128 = (u64::MAX as u128) << 64;
+
+/// A snapshot of the price accumulator
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriceObservation {
+    /// Σ spot price × seconds, Q64.64 lamports per base unit, wrapping
+    pub price_cumulative: u128,
+    /// Unix seconds
+    pub timestamp: i64,
+}
+
+impl PriceObservation {
+    /// Carry the observation forward to `now` at the `price` that has held since.
+    /// A clock that runs backwards accrues nothing.
+    pub fn advance(&self, price: u128, now: i64) -> PriceObservation {
+        if now <= self.timestamp { return *self; }
+        let elapsed = now.abs_diff(self.timestamp) as u128;
+        PriceObservation {
+            price_cumulative: self.price_cumulative.wrapping_add(price.wrapping_mul(elapsed)),
+            timestamp: now,
+        }
+    }
+}
+
+/// Time-weighted average price between two observations, Q64.64.
+/// Wrapping subtraction keeps it right across one accumulator overflow.
+/// None unless `end` is strictly later than `start`.
+pub fn twap(start: &PriceObservation, end: &PriceObservation) -> Option<u128> {
+    if end.timestamp <= start.timestamp { return None; }
+    let elapsed = end.timestamp.abs_diff(start.timestamp) as u128;
+    Some(end.price_cumulative.wrapping_sub(start.price_cumulative) / elapsed)
+}
+
+// ─── SOL vault ────────────────────────────────────────────────────────────────
+
+/// Whether a curve's SOL vault holds at least its recorded `real_sol_balance`
+/// on top of the vault's rent-exempt floor. Extra lamports (someone topping the
+/// vault up directly, rounding dust) are fine; a shortfall means the books drifted.
+pub fn vault_covers_balance(real_sol_balance: u64, vault_lamports: u64, rent_floor: u64) -> bool {
+    vault_lamports.saturating_sub(rent_floor) >= real_sol_balance
 }
 
 // ─── Unit Tests ───────────────────────────────────────────────────────────────
@@ -373,6 +1378,20 @@ mod tests {
 
     // ─── 9. Constant product invariant holds ─────────────────────────────
 
+    #[test]
+    fn test_rounding_dust_stays_in_the_curve() {
+        // k = 100. A 3-lamport buy moves the SOL reserve to 13; 100 / 13 = 7.69,
+        // so the token reserve rounds up to 8 and the buyer gets 2, not 3
+        let tokens_out = calculate_tokens_out(10, 10, 3).unwrap();
+        assert_eq!(tokens_out, 2);
+        assert!(13 * (10 - tokens_out) >= 100, "k must not shrink");
+
+        // Same on the way out: 3 tokens in leaves ⌈100 / 13⌉ = 8 SOL behind
+        let sol_out = calculate_sol_out(10, 10, 3).unwrap();
+        assert_eq!(sol_out, 2);
+        assert!((10 - sol_out) * 13 >= 100, "k must not shrink");
+    }
+
     #[test]
     fn test_constant_product_invariant() {
         let vsr = INITIAL_VIRTUAL_SOL_RESERVE;
@@ -402,12 +1421,274 @@ mod tests {
         println!("k drift:  {:.6}% (should be near 0)", drift_pct);
     }
 
-    // ─── 10. Price display ────────────────────────────────────────────────
+    // ─── 10. Creator fee split ────────────────────────────────────────────
 
     #[test]
-    fn test_initial_price_display() {
-        let price = current_price_per_token(
-            INITIAL_VIRTUAL_SOL_RESERVE,
+    fn test_split_fee_adds_up() {
+        let (_, fee) = deduct_fee(1_000_000_000); // fee on 1 SOL
+        let (platform_fee, creator_fee) = split_fee(fee, 2_500).unwrap(); // 25% to creator
+
+        assert_eq!(platform_fee + creator_fee, fee, "split must not create or lose lamports");
+        assert_eq!(creator_fee, fee / 4);
+
+        // Dust from odd amounts stays with the platform
+        let (platform_fee, creator_fee) = split_fee(3, 5_000).unwrap();
+        assert_eq!((platform_fee, creator_fee), (2, 1));
+    }
+
+    #[test]
+    fn test_split_fee_bounds() {
+        assert_eq!(split_fee(1_000, 0), Some((1_000, 0)));
+        assert_eq!(split_fee(1_000, BPS_DENOMINATOR), Some((0, 1_000)));
+        assert!(split_fee(1_000, BPS_DENOMINATOR + 1).is_none(), "share above 100% must be rejected");
+    }
+
+    #[test]
+    fn test_referral_split_comes_from_platform_side() {
+        let fee = 10_000_000u64; // fee on 1 SOL
+        let (platform, creator, referral) = split_fee_with_referral(fee, 2_000, 3_000).unwrap();
+
+        assert_eq!(platform + creator + referral, fee, "three-way split must add up");
+        assert_eq!(referral, 3_000_000);
+
+        // Creator's cut is unchanged by the referral
+        let (_, creator_no_ref) = split_fee(fee, 2_000).unwrap();
+        assert_eq!(creator, creator_no_ref);
+
+        // No referrer → identical to the two-way split
+        let (platform_no_ref, _) = split_fee(fee, 2_000).unwrap();
+        assert_eq!(split_fee_with_referral(fee, 2_000, 0), Some((platform_no_ref, creator, 0)));
+    }
+
+    #[test]
+    fn test_referral_split_rejects_shares_over_100_percent() {
+        assert!(split_fee_with_referral(1_000, 6_000, 5_000).is_none());
+        assert_eq!(split_fee_with_referral(1_000, 6_000, 4_000), Some((0, 600, 400)));
+    }
+
+    // ─── 11. Launch fee decay ─────────────────────────────────────────────
+
+    #[test]
+    fn test_launch_fee_decays_linearly_to_base() {
+        let base     = PLATFORM_FEE_BPS; // 1%
+        let launch   = 5_000u64;         // 50% right at creation
+        let duration = 60u64;
+
+        assert_eq!(launch_fee_bps(base, launch, 0, duration), launch, "full launch fee at t=0");
+        assert_eq!(launch_fee_bps(base, launch, 30, duration), base + (launch - base) / 2);
+        assert_eq!(launch_fee_bps(base, launch, duration, duration), base, "base fee once decay ends");
+        assert_eq!(launch_fee_bps(base, launch, 10 * duration, duration), base);
+
+        // Never increases as time passes
+        let mut last = u64::MAX;
+        for t in 0..=duration {
+            let fee = launch_fee_bps(base, launch, t, duration);
+            assert!(fee <= last, "fee went up at t={}", t);
+            assert!(fee >= base && fee <= launch);
+            last = fee;
+        }
+    }
+
+    #[test]
+    fn test_launch_fee_disabled_schedule_is_base() {
+        assert_eq!(launch_fee_bps(100, 5_000, 0, 0), 100, "zero duration disables the schedule");
+        assert_eq!(launch_fee_bps(100, 50, 0, 60), 100, "launch fee below base is ignored");
+    }
+
+    #[test]
+    fn test_launch_fee_rounds_up() {
+        // 1 bps spread over 3 units: 2/3 bps left at t=1 rounds up to 1
+        assert_eq!(launch_fee_bps(100, 101, 1, 3), 101);
+        assert_eq!(launch_fee_bps(100, 101, 3, 3), 100);
+    }
+
+    // ─── 12. Curve shapes ─────────────────────────────────────────────────
+
+    const LAUNCH_PRICE: u64 = 27_958_993; // ≈ constant-product launch price in PRICE_SCALE units
+
+    fn test_shapes() -> Vec<(&'static str, Box<dyn PricingCurve>)> {
+        vec![
+            ("constant product", Box::new(ConstantProduct)),
+            ("linear", Box::new(Linear { start_price: LAUNCH_PRICE, end_price: 400_000_000 })),
+            ("exponential", Box::new(Exponential {
+                start_price: LAUNCH_PRICE,
+                doubling_tokens: 200_000_000_000_000, // 200M tokens
+            })),
+            ("stepped", Box::new(Stepped {
+                start_price: LAUNCH_PRICE,
+                step_tokens: 10_000_000_000_000, // 10M tokens
+                step_increment: 5_000_000,
+            })),
+        ]
+    }
+
+    #[test]
+    fn test_constant_product_shape_matches_core_functions() {
+        let state = CurveState::initial();
+        assert_eq!(
+            ConstantProduct.tokens_out(&state, 1_000_000_000),
+            calculate_tokens_out(INITIAL_VIRTUAL_SOL_RESERVE, INITIAL_VIRTUAL_TOKEN_RESERVE, 1_000_000_000)
+        );
+        assert_eq!(
+            ConstantProduct.sol_out(&state, 1_000_000_000_000),
+            calculate_sol_out(INITIAL_VIRTUAL_SOL_RESERVE, INITIAL_VIRTUAL_TOKEN_RESERVE, 1_000_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_every_shape_price_increases_after_each_buy() {
+        for (name, shape) in test_shapes() {
+            let mut state = CurveState::initial();
+            let sol_each  = 1_000_000_000u64; // 1 SOL each time
+            let first     = shape.tokens_out(&state, sol_each).unwrap();
+            let mut last  = u64::MAX;
+
+            for i in 0..20 {
+                let tokens_out = shape.tokens_out(&state, sol_each).unwrap();
+                // Stepped curves are flat inside a step, so allow equal fills
+                assert!(tokens_out <= last, "{}: buy {} got more tokens than the one before", name, i + 1);
+                assert!(tokens_out > 0, "{}: buy {} returned nothing", name, i + 1);
+                state = state.after_buy(sol_each, tokens_out).unwrap();
+                last = tokens_out;
+            }
+
+            assert!(last < first, "{}: price never moved over 20 SOL of buys", name);
+            println!("{}: first 1 SOL → {} tokens, 20th → {}", name, first / 1_000_000, last / 1_000_000);
+        }
+    }
+
+    #[test]
+    fn test_every_shape_sell_returns_less_as_supply_falls() {
+        for (name, shape) in test_shapes() {
+            let mut state = CurveState::initial();
+            let tokens = shape.tokens_out(&state, 20_000_000_000).unwrap(); // 20 SOL in
+            state = state.after_buy(20_000_000_000, tokens).unwrap();
+
+            let chunk    = tokens / 10;
+            let mut last = u64::MAX;
+            for i in 0..10 {
+                let sol = shape.sol_out(&state, chunk).unwrap();
+                assert!(sol <= last, "{}: sell {} paid more than the one before", name, i + 1);
+                state = state.after_sell(chunk, sol).unwrap();
+                last = sol;
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_shape_has_no_free_lunch() {
+        for (name, shape) in test_shapes() {
+            let mut state = CurveState::initial();
+
+            // Move the curve to a few different points before round-tripping
+            for (step, sol_in) in [1_000_000u64, 1_000_000_000, 7_777_777_777, 25_000_000_000].into_iter().enumerate() {
+                let tokens = shape.tokens_out(&state, sol_in).unwrap();
+                let mut after_buy = state;
+                after_buy = after_buy.after_buy(sol_in, tokens).unwrap();
+
+                if tokens > 0 {
+                    let sol_back = shape.sol_out(&after_buy, tokens).unwrap();
+                    assert!(
+                        sol_back <= sol_in,
+                        "{}: round trip {} paid out {} for {} in",
+                        name, step, sol_back, sol_in
+                    );
+                }
+
+                state = after_buy;
+            }
+
+            // Selling everything back never returns more than was paid in total
+            let total_in = state.real_sol_balance;
+            let sol_back = shape.sol_out(&state, state.tokens_sold).unwrap();
+            assert!(sol_back <= total_in, "{}: unwinding paid {} for {} in", name, sol_back, total_in);
+        }
+    }
+
+    #[test]
+    fn test_integral_shapes_reject_bad_input() {
+        let state = CurveState::initial();
+        let linear = Linear { start_price: LAUNCH_PRICE, end_price: 400_000_000 };
+
+        assert!(linear.tokens_out(&state, 0).is_none(), "zero SOL buy should return None");
+        assert!(linear.sol_out(&state, 0).is_none(), "zero token sell should return None");
+        assert!(linear.sol_out(&state, 1).is_none(), "cannot sell tokens that were never sold");
+
+        assert!(!Linear { start_price: 10, end_price: 5 }.is_valid(), "falling linear curve is invalid");
+        assert!(!Exponential { start_price: 10, doubling_tokens: 0 }.is_valid());
+        assert!(!Stepped { start_price: 0, step_tokens: 1, step_increment: 1 }.is_valid());
+    }
+
+    #[test]
+    fn test_shapes_that_cannot_sell_out_are_invalid() {
+        // Each one prices its first tokens fine but overflows before the reserve runs out
+        let exponential = Exponential { start_price: 1, doubling_tokens: 1 };
+        assert!(exponential.sol_in(&CurveState::initial(), 10).is_some());
+        assert!(!exponential.is_valid(), "2^(793M) can't be priced");
+
+        assert!(!Linear { start_price: 1, end_price: u64::MAX }.is_valid(), "total cost past u64 lamports");
+        assert!(!Stepped { start_price: 1, step_tokens: 1, step_increment: u64::MAX }.is_valid());
+
+        // The shapes the rest of these tests launch are fine
+        assert!(Linear { start_price: LAUNCH_PRICE, end_price: 400_000_000 }.is_valid());
+        assert!(Exponential { start_price: 1_000, doubling_tokens: INITIAL_REAL_TOKEN_RESERVE / 20 }.is_valid());
+        assert!(Stepped { start_price: LAUNCH_PRICE, step_tokens: INITIAL_REAL_TOKEN_RESERVE / 10, step_increment: LAUNCH_PRICE }.is_valid());
+    }
+
+    #[test]
+    fn test_stepped_cost_matches_hand_computation() {
+        let shape = Stepped { start_price: 2 * PRICE_SCALE as u64, step_tokens: 100, step_increment: PRICE_SCALE as u64 };
+        let state = CurveState::initial();
+
+        // 100 tokens at 2 lamports + 50 tokens at 3 lamports = 350 lamports
+        assert_eq!(integral_buy_cost(&shape, &state, 150), Some(350));
+        assert_eq!(shape.tokens_out(&state, 350), Some(150));
+        assert_eq!(shape.tokens_out(&state, 349), Some(149));
+    }
+
+    #[test]
+    fn test_integral_search_is_bounded_by_the_reserve() {
+        // Counts F(s) evaluations — each one is the bulk of a search step's compute
+        struct Counted<'a, C>(&'a C, std::cell::Cell<u32>);
+        impl<C: IntegralCurve> IntegralCurve for Counted<'_, C> {
+            fn cumulative_cost(&self, state: &CurveState, s: u64) -> Option<u128> {
+                self.1.set(self.1.get() + 1);
+                self.0.cumulative_cost(state, s)
+            }
+        }
+
+        // Worst case: a steep exponential (20 doublings) and more SOL than the whole curve costs
+        let shape   = Exponential { start_price: 1_000, doubling_tokens: INITIAL_REAL_TOKEN_RESERVE / 20 };
+        let state   = CurveState::initial();
+        let counted = Counted(&shape, std::cell::Cell::new(0));
+
+        assert_eq!(integral_tokens_out(&counted, &state, u64::MAX), Some(state.real_token_reserve));
+        // Two F evaluations per halving of [0, real_token_reserve]
+        let steps = u64::BITS - state.real_token_reserve.leading_zeros();
+        assert!(counted.1.get() <= 2 * steps, "{} evaluations for {} steps", counted.1.get(), steps);
+
+        // The capped buy still charges only what the reserve costs
+        let cost = integral_buy_cost(&shape, &state, state.real_token_reserve).unwrap();
+        assert_eq!(capped_buy(&shape, &state, u64::MAX, u64::MAX), Ok((cost, state.real_token_reserve)));
+    }
+
+    #[test]
+    fn test_exponential_doubles_over_doubling_interval() {
+        // 2^1 and 2^2 come out exact, the fraction stays within float tolerance
+        assert_eq!(exp2_q48(1, 0), Some(2 * Q48_ONE));
+        assert_eq!(exp2_q48(2, 0), Some(4 * Q48_ONE));
+
+        let sqrt2 = exp2_q48(0, Q48_ONE / 2).unwrap() as f64 / Q48_ONE as f64;
+        assert!((sqrt2 - std::f64::consts::SQRT_2).abs() < 1e-12, "2^0.5 = {}", sqrt2);
+        assert!(exp2_q48(65, 0).is_none(), "overflowing exponent should return None");
+    }
+
+    // ─── 13. Price display ────────────────────────────────────────────────
+
+    #[test]
+    fn test_initial_price_display() {
+        let price = current_price_per_token(
+            INITIAL_VIRTUAL_SOL_RESERVE,
             INITIAL_VIRTUAL_TOKEN_RESERVE,
         );
         println!("Initial price: {:.10} SOL per token", price);
@@ -418,4 +1699,711 @@ mod tests {
         assert!(price < 0.001, "initial price should be < 0.001 SOL");
         assert!(price > 0.0,   "initial price should be > 0");
     }
-}
\ No newline at end of file
+
+    // ─── 14. Exact-output buys ────────────────────────────────────────────
+
+    #[test]
+    fn test_sol_in_round_trips_through_tokens_out() {
+        // Several curve positions × several target sizes
+        for sol_reserve in [INITIAL_VIRTUAL_SOL_RESERVE, 47_123_456_789, 110_000_000_000] {
+            let k = INITIAL_VIRTUAL_SOL_RESERVE as u128 * INITIAL_VIRTUAL_TOKEN_RESERVE as u128;
+            let token_reserve = (k / sol_reserve as u128) as u64;
+
+            for want in [1u64, 999, 1_000_000, 123_456_789_012, 200_000_000_000_000] {
+                let sol_in = calculate_sol_in(sol_reserve, token_reserve, want).unwrap();
+                let got    = calculate_tokens_out(sol_reserve, token_reserve, sol_in).unwrap();
+                assert!(got >= want, "paid {} for {} but only got {}", sol_in, want, got);
+
+                // And it is the cheapest such amount — one lamport less falls short
+                if sol_in > 1 {
+                    let short = calculate_tokens_out(sol_reserve, token_reserve, sol_in - 1).unwrap();
+                    assert!(short < want, "{} lamports would already buy {}", sol_in - 1, want);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sol_in_rejects_impossible_outputs() {
+        let (vs, vt) = (INITIAL_VIRTUAL_SOL_RESERVE, INITIAL_VIRTUAL_TOKEN_RESERVE);
+        assert!(calculate_sol_in(vs, vt, 0).is_none(), "zero token buy should return None");
+        assert!(calculate_sol_in(vs, vt, vt).is_none(), "cannot drain the whole virtual reserve");
+    }
+
+    #[test]
+    fn test_every_shape_sol_in_covers_the_tokens() {
+        for (name, shape) in test_shapes() {
+            let mut state = CurveState::initial();
+            let bought = shape.tokens_out(&state, 5_000_000_000).unwrap();
+            state = state.after_buy(5_000_000_000, bought).unwrap();
+
+            for want in [1_000_000u64, 77_777_777_777, 100_000_000_000_000] {
+                let sol_in = shape.sol_in(&state, want).unwrap();
+                let got    = shape.tokens_out(&state, sol_in).unwrap();
+                assert!(got >= want, "{}: paid {} for {} but only got {}", name, sol_in, want, got);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gross_up_leaves_net_after_fee() {
+        for fee_bps in [0u64, 1, 100, 333, 2_500, 9_999] {
+            for net in [1u64, 99, 1_000_000_000, 123_456_789_012] {
+                let gross = gross_up_for_fee(net, fee_bps).unwrap();
+                let fee   = gross * fee_bps / BPS_DENOMINATOR;
+                assert!(gross - fee >= net, "{} bps: {} gross leaves less than {}", fee_bps, gross, net);
+
+                let fee_short = (gross - 1) * fee_bps / BPS_DENOMINATOR;
+                assert!(gross - 1 - fee_short < net, "{} bps: {} is not the smallest gross", fee_bps, gross);
+            }
+        }
+        assert!(gross_up_for_fee(1, BPS_DENOMINATOR).is_none(), "100% fee leaves nothing");
+    }
+
+    // ─── 15. Exact-output sells ───────────────────────────────────────────
+
+    #[test]
+    fn test_tokens_in_round_trips_through_sol_out() {
+        for sol_reserve in [35_000_000_000u64, 47_123_456_789, 110_000_000_000] {
+            let k = INITIAL_VIRTUAL_SOL_RESERVE as u128 * INITIAL_VIRTUAL_TOKEN_RESERVE as u128;
+            let token_reserve = (k / sol_reserve as u128) as u64;
+
+            for want in [1u64, 999, 1_000_000, 1_234_567_890, 4_999_999_999] {
+                let tokens_in = calculate_tokens_in(sol_reserve, token_reserve, want).unwrap();
+                let got       = calculate_sol_out(sol_reserve, token_reserve, tokens_in).unwrap();
+                assert!(got >= want, "sold {} for {} but only got {}", tokens_in, want, got);
+
+                // And it is the fewest such tokens — one less falls short
+                if tokens_in > 1 {
+                    let short = calculate_sol_out(sol_reserve, token_reserve, tokens_in - 1).unwrap();
+                    assert!(short < want, "{} tokens would already pay {}", tokens_in - 1, want);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_tokens_in_rejects_impossible_outputs() {
+        let (vs, vt) = (INITIAL_VIRTUAL_SOL_RESERVE, INITIAL_VIRTUAL_TOKEN_RESERVE);
+        assert!(calculate_tokens_in(vs, vt, 0).is_none(), "zero SOL sell should return None");
+        assert!(calculate_tokens_in(vs, vt, vs).is_none(), "cannot drain the whole virtual reserve");
+    }
+
+    #[test]
+    fn test_every_shape_tokens_in_covers_the_sol() {
+        for (name, shape) in test_shapes() {
+            let mut state = CurveState::initial();
+            let bought = shape.tokens_out(&state, 20_000_000_000).unwrap();
+            state = state.after_buy(20_000_000_000, bought).unwrap();
+
+            for want in [1_000u64, 123_456_789, 10_000_000_000] {
+                let tokens_in = shape.tokens_in(&state, want).unwrap();
+                let got       = shape.sol_out(&state, tokens_in).unwrap();
+                assert!(got >= want, "{}: sold {} for {} but only got {}", name, tokens_in, want, got);
+                assert!(tokens_in <= bought, "{}: needed more tokens than were ever sold", name);
+            }
+        }
+    }
+
+    // ─── 16. Final buy is capped at completion ────────────────────────────
+
+    // Helper: a constant-product curve with only `reserve` tokens left to sell
+    fn nearly_sold_out(reserve: u64) -> CurveState {
+        let mut state = CurveState::initial();
+        let sold = INITIAL_REAL_TOKEN_RESERVE - reserve;
+        let cost = calculate_sol_in(state.virtual_sol_reserve, state.virtual_token_reserve, sold).unwrap();
+        state = state.after_buy(cost, sold).unwrap();
+        state
+    }
+
+    #[test]
+    fn test_capped_buy_passes_small_buys_through() {
+        let state = CurveState::initial();
+        let (sol_used, tokens_out) = capped_buy(&ConstantProduct, &state, 1_000_000_000, u64::MAX).unwrap();
+        assert_eq!(sol_used, 1_000_000_000);
+        assert_eq!(Some(tokens_out), ConstantProduct.tokens_out(&state, 1_000_000_000));
+    }
+
+    #[test]
+    fn test_capped_buy_sells_out_the_reserve_and_refunds() {
+        let state = nearly_sold_out(1_000_000_000_000); // 1M tokens left
+        let (sol_used, tokens_out) = capped_buy(&ConstantProduct, &state, 50_000_000_000, u64::MAX).unwrap();
+
+        assert_eq!(tokens_out, state.real_token_reserve, "last buyer gets exactly what is left");
+        assert!(sol_used < 50_000_000_000, "overshoot should be refunded");
+        assert_eq!(Some(sol_used), ConstantProduct.sol_in(&state, tokens_out), "charged only what the tokens cost");
+    }
+
+    #[test]
+    fn test_capped_buy_stops_at_grad_threshold() {
+        let mut state = CurveState::initial();
+        let bought = ConstantProduct.tokens_out(&state, 80_000_000_000).unwrap();
+        state = state.after_buy(80_000_000_000, bought).unwrap();
+
+        let (sol_used, tokens_out) = capped_buy(&ConstantProduct, &state, 10_000_000_000, GRADUATION_THRESHOLD).unwrap();
+        assert_eq!(sol_used, GRADUATION_THRESHOLD - 80_000_000_000, "buy fills only up to the threshold");
+        assert_eq!(Some(tokens_out), ConstantProduct.tokens_out(&state, sol_used));
+
+        state = state.after_buy(sol_used, tokens_out).unwrap();
+        assert_eq!(
+            capped_buy(&ConstantProduct, &state, 1, GRADUATION_THRESHOLD),
+            Err(TradeError::CurveComplete),
+            "completed curve takes no more buys"
+        );
+    }
+
+    #[test]
+    fn test_capped_fill_scales_the_slippage_floor() {
+        // Uncapped: the floor applies as quoted
+        assert_eq!(min_tokens_for_fill(1_000, 500, 500), 1_000);
+        // Half filled: half the floor, rounded up so the price bound never loosens
+        assert_eq!(min_tokens_for_fill(1_000, 500, 250), 500);
+        assert_eq!(min_tokens_for_fill(1_001, 500, 250), 501);
+        assert_eq!(min_tokens_for_fill(u64::MAX, u64::MAX, u64::MAX - 1), u64::MAX - 1);
+
+        // The final buyer quoted 10 SOL before the curve hit its threshold
+        let mut state = CurveState::initial();
+        let bought = ConstantProduct.tokens_out(&state, 80_000_000_000).unwrap();
+        state = state.after_buy(80_000_000_000, bought).unwrap();
+        let quoted = ConstantProduct.tokens_out(&state, 10_000_000_000).unwrap();
+
+        let (sol_used, tokens_out) = capped_buy(&ConstantProduct, &state, 10_000_000_000, GRADUATION_THRESHOLD).unwrap();
+        assert!(tokens_out < quoted, "the capped fill is smaller than quoted");
+        assert!(tokens_out >= min_tokens_for_fill(quoted, 10_000_000_000, sol_used), "but no worse priced");
+    }
+
+    #[test]
+    fn test_capped_buy_reports_pricing_failures_as_overflow() {
+        // A shape that can't price anything, as if its arithmetic overflowed
+        struct Unpriceable;
+        impl PricingCurve for Unpriceable {
+            fn tokens_out(&self, _: &CurveState, _: u64) -> Option<u64> { None }
+            fn sol_out(&self, _: &CurveState, _: u64) -> Option<u64> { None }
+            fn sol_in(&self, _: &CurveState, _: u64) -> Option<u64> { None }
+            fn tokens_in(&self, _: &CurveState, _: u64) -> Option<u64> { None }
+            fn spot_price(&self, _: &CurveState) -> Option<u128> { None }
+        }
+
+        let state = CurveState::initial();
+        assert_eq!(capped_buy(&Unpriceable, &state, 1_000, GRADUATION_THRESHOLD), Err(TradeError::MathOverflow("tokens_out")));
+        assert_eq!(capped_buy(&ConstantProduct, &state, 0, GRADUATION_THRESHOLD), Err(TradeError::ZeroAmount));
+    }
+
+    // ─── 17. SOL vault floor ──────────────────────────────────────────────
+
+    const RENT_FLOOR: u64 = 890_880; // rent-exempt minimum for a 0-byte account
+
+    // The round trip through the real vault lives in tests/program_flow.rs
+
+    #[test]
+    fn test_vault_shortfall_is_detected() {
+        assert!(vault_covers_balance(0, RENT_FLOOR, RENT_FLOOR));
+        assert!(vault_covers_balance(5, RENT_FLOOR + 7, RENT_FLOOR), "surplus lamports are fine");
+        assert!(!vault_covers_balance(5, RENT_FLOOR + 4, RENT_FLOOR), "shortfall must be caught");
+        assert!(!vault_covers_balance(1, 0, RENT_FLOOR), "empty vault covers nothing");
+    }
+
+    // ─── 18. Trade execution ──────────────────────────────────────────────
+
+    #[test]
+    fn test_execute_buy_matches_step_by_step_math() {
+        let state   = CurveState::initial();
+        let outcome = execute_buy(&ConstantProduct, &state, 1_000_000_000, PLATFORM_FEE_BPS, GRADUATION_THRESHOLD).unwrap();
+        let (tokens_out, new_vsr, new_vtr, fee) = do_buy(state.virtual_sol_reserve, state.virtual_token_reserve, 1_000_000_000);
+
+        assert_eq!(outcome.token_amount, tokens_out);
+        assert_eq!(outcome.fee, fee);
+        assert_eq!(outcome.sol_amount, 1_000_000_000);
+        assert_eq!(outcome.sol_net + outcome.fee, outcome.sol_amount);
+        assert_eq!(outcome.state.virtual_sol_reserve, new_vsr);
+        assert_eq!(outcome.state.virtual_token_reserve, new_vtr);
+        assert_eq!(outcome.state.tokens_sold, tokens_out);
+    }
+
+    #[test]
+    fn test_execute_sell_matches_step_by_step_math() {
+        let state  = CurveState::initial();
+        let bought = execute_buy(&ConstantProduct, &state, 5_000_000_000, PLATFORM_FEE_BPS, GRADUATION_THRESHOLD).unwrap();
+        let after  = bought.state;
+
+        let outcome = execute_sell(&ConstantProduct, &after, bought.token_amount, PLATFORM_FEE_BPS).unwrap();
+        let (sol_to_seller, new_vsr, new_vtr, fee) =
+            do_sell(after.virtual_sol_reserve, after.virtual_token_reserve, bought.token_amount);
+
+        assert_eq!(outcome.sol_net, sol_to_seller);
+        assert_eq!(outcome.fee, fee);
+        assert_eq!(outcome.state.virtual_sol_reserve, new_vsr);
+        assert_eq!(outcome.state.virtual_token_reserve, new_vtr);
+        assert_eq!(outcome.state.tokens_sold, 0);
+    }
+
+    #[test]
+    fn test_execute_buy_charges_only_the_filled_part() {
+        let state   = nearly_sold_out(1_000_000_000_000);
+        let outcome = execute_buy(&ConstantProduct, &state, 50_000_000_000, PLATFORM_FEE_BPS, u64::MAX).unwrap();
+
+        assert_eq!(outcome.token_amount, state.real_token_reserve);
+        assert!(outcome.sol_amount < 50_000_000_000, "overshoot should not be charged");
+        assert_eq!(outcome.fee, fee_for(outcome.sol_amount, PLATFORM_FEE_BPS).unwrap(), "fee is on the charged amount");
+        assert_eq!(outcome.state.real_token_reserve, 0);
+    }
+
+    #[test]
+    fn test_execute_reports_why_a_trade_failed() {
+        let state = CurveState::initial();
+        assert_eq!(execute_buy(&ConstantProduct, &state, 0, PLATFORM_FEE_BPS, GRADUATION_THRESHOLD), Err(TradeError::ZeroAmount));
+        assert_eq!(execute_buy(&ConstantProduct, &state, 1, BPS_DENOMINATOR + 1, GRADUATION_THRESHOLD), Err(TradeError::InvalidFeeBps));
+        assert_eq!(execute_buy(&ConstantProduct, &state, 1_000, PLATFORM_FEE_BPS, 0), Err(TradeError::CurveComplete));
+        // A 100% fee leaves nothing to buy with — the curve is still live
+        assert_eq!(execute_buy(&ConstantProduct, &state, 1_000, BPS_DENOMINATOR, GRADUATION_THRESHOLD), Err(TradeError::ZeroAmount));
+        assert_eq!(
+            execute_buy_exact_tokens(&ConstantProduct, &state, INITIAL_REAL_TOKEN_RESERVE + 1, PLATFORM_FEE_BPS),
+            Err(TradeError::InsufficientTokens)
+        );
+        assert_eq!(execute_sell(&ConstantProduct, &state, 0, PLATFORM_FEE_BPS), Err(TradeError::ZeroAmount));
+        assert_eq!(
+            execute_sell_for_exact_sol(&ConstantProduct, &state, 1_000, PLATFORM_FEE_BPS),
+            Err(TradeError::InsufficientSol),
+            "nothing raised yet, nothing to pay out"
+        );
+    }
+
+    // ─── 19. Quotes ───────────────────────────────────────────────────────
+
+    #[test]
+    fn test_quote_buy_fields_add_up() {
+        let state = CurveState::initial();
+        let quote = quote_buy(&ConstantProduct, &state, 1_000_000_000, PLATFORM_FEE_BPS, GRADUATION_THRESHOLD).unwrap();
+        let trade = execute_buy(&ConstantProduct, &state, 1_000_000_000, PLATFORM_FEE_BPS, GRADUATION_THRESHOLD).unwrap();
+
+        assert_eq!(quote.token_amount, trade.token_amount, "quote must price exactly like the instruction");
+        assert_eq!(quote.sol_gross, 1_000_000_000);
+        assert_eq!(quote.sol_net + quote.fee, quote.sol_gross);
+        assert_eq!(quote.state_after, trade.state);
+        assert_eq!(quote.avg_price, 1_000_000_000 * PRICE_SCALE / quote.token_amount as u128);
+    }
+
+    #[test]
+    fn test_quote_spot_prices_match_reserves() {
+        let state = CurveState::initial();
+        let quote = quote_buy(&ConstantProduct, &state, 5_000_000_000, PLATFORM_FEE_BPS, GRADUATION_THRESHOLD).unwrap();
+
+        assert_eq!(quote.spot_price_before, 27_958_993, "launch price in PRICE_SCALE units");
+        let after = quote.state_after;
+        assert_eq!(quote.spot_price_after, after.virtual_sol_reserve as u128 * PRICE_SCALE / after.virtual_token_reserve as u128);
+        assert!(quote.spot_price_after > quote.spot_price_before, "buy should push the price up");
+
+        // Buyer pays more than the launch spot price: slippage plus the fee
+        assert!(quote.avg_price > quote.spot_price_before);
+    }
+
+    #[test]
+    fn test_quote_price_impact_grows_with_size() {
+        let state = CurveState::initial();
+        let small = quote_buy(&ConstantProduct, &state, 100_000_000, PLATFORM_FEE_BPS, GRADUATION_THRESHOLD).unwrap();
+        let large = quote_buy(&ConstantProduct, &state, 20_000_000_000, PLATFORM_FEE_BPS, GRADUATION_THRESHOLD).unwrap();
+
+        assert!(small.price_impact_bps < 50, "0.1 SOL should barely move a 30 SOL curve: {}", small.price_impact_bps);
+        assert!(large.price_impact_bps > small.price_impact_bps);
+        // Constant product: the fill price is exactly vs·(vs+Δ) / vs² away, so 20 SOL into 30 ≈ +66%
+        assert!((6_500..6_700).contains(&large.price_impact_bps), "{}", large.price_impact_bps);
+    }
+
+    #[test]
+    fn test_quote_sell_fields_add_up() {
+        let start  = CurveState::initial();
+        let bought = execute_buy(&ConstantProduct, &start, 10_000_000_000, PLATFORM_FEE_BPS, GRADUATION_THRESHOLD).unwrap();
+        let quote  = quote_sell(&ConstantProduct, &bought.state, bought.token_amount / 2, PLATFORM_FEE_BPS).unwrap();
+
+        assert_eq!(quote.sol_net + quote.fee, quote.sol_gross);
+        assert!(quote.spot_price_after < quote.spot_price_before, "sell should push the price down");
+        assert!(quote.avg_price < quote.spot_price_before, "seller receives less than spot");
+        assert!(quote.price_impact_bps > 0);
+        assert_eq!(quote.state_after.tokens_sold, bought.token_amount - bought.token_amount / 2);
+    }
+
+    #[test]
+    fn test_every_shape_quotes_a_rising_spot_price() {
+        for (name, shape) in test_shapes() {
+            let state = CurveState::initial();
+            let quote = quote_buy(shape.as_ref(), &state, 10_000_000_000, PLATFORM_FEE_BPS, GRADUATION_THRESHOLD).unwrap();
+            assert!(quote.spot_price_before > 0, "{}: zero launch price", name);
+            assert!(quote.spot_price_after >= quote.spot_price_before, "{}: price fell after a buy", name);
+        }
+    }
+
+    #[test]
+    fn test_quote_rejects_what_execute_rejects() {
+        let state = CurveState::initial();
+        assert_eq!(quote_buy(&ConstantProduct, &state, 0, PLATFORM_FEE_BPS, GRADUATION_THRESHOLD), Err(TradeError::ZeroAmount));
+        assert_eq!(quote_sell(&ConstantProduct, &state, 0, PLATFORM_FEE_BPS), Err(TradeError::ZeroAmount));
+    }
+
+    // ─── 20. Fixed-point price and market cap ─────────────────────────────
+
+    #[test]
+    fn test_launch_price_q64() {
+        let price = price_q64(INITIAL_VIRTUAL_SOL_RESERVE, INITIAL_VIRTUAL_TOKEN_RESERVE).unwrap();
+        // 30e9 / 1.073e15 lamports per base unit
+        assert_eq!(price, (INITIAL_VIRTUAL_SOL_RESERVE as u128 * Q64_ONE) / INITIAL_VIRTUAL_TOKEN_RESERVE as u128);
+        assert_eq!(price >> 64, 0, "launch price is a fraction of a lamport per base unit");
+
+        // Agrees with the PRICE_SCALE spot price to within its precision
+        let scaled = ConstantProduct.spot_price(&CurveState::initial()).unwrap();
+        assert_eq!(price * PRICE_SCALE / Q64_ONE, scaled);
+    }
+
+    #[test]
+    fn test_price_q64_rounds_down_and_handles_extremes() {
+        // 1 / 3 = 0x5555…5 with the remainder dropped
+        assert_eq!(price_q64(1, 3), Some(Q64_ONE / 3));
+        assert!(price_q64(1, 3).unwrap() * 3 < Q64_ONE);
+        assert_eq!(price_q64(u64::MAX, 1), Some((u64::MAX as u128) << 64));
+        assert_eq!(price_q64(0, 1), Some(0));
+        assert_eq!(price_q64(1, 0), None);
+    }
+
+    #[test]
+    fn test_market_caps_at_launch() {
+        let state = CurveState::initial();
+        // 30 SOL × 1000M / 1073M, rounded down to the lamport
+        assert_eq!(fully_diluted_market_cap(&ConstantProduct, &state), Some(27_958_993_476));
+        assert_eq!(circulating_market_cap(&ConstantProduct, &state), Some(0), "nothing sold yet");
+    }
+
+    #[test]
+    fn test_market_caps_after_buys() {
+        let state = execute_buy(&ConstantProduct, &CurveState::initial(), 10_000_000_000, PLATFORM_FEE_BPS, GRADUATION_THRESHOLD)
+            .unwrap()
+            .state;
+        let fdv  = fully_diluted_market_cap(&ConstantProduct, &state).unwrap();
+        let circ = circulating_market_cap(&ConstantProduct, &state).unwrap();
+
+        assert!(circ < fdv);
+        // Priced off the rounded Q64.64 spot price
+        let via_price = (price_q64(state.virtual_sol_reserve, state.virtual_token_reserve).unwrap() * state.tokens_sold as u128) >> 64;
+        assert_eq!(circ as u128, via_price);
+        // which loses up to `supply` × 2^-64 lamports against the reserves — never more than 1 here
+        let exact = state.virtual_sol_reserve as u128 * state.tokens_sold as u128 / state.virtual_token_reserve as u128;
+        assert!(exact - via_price <= 1);
+        assert_eq!(market_cap_lamports(state.virtual_sol_reserve, state.virtual_token_reserve, state.tokens_sold), Some(exact as u64));
+    }
+
+    #[test]
+    fn test_market_caps_follow_the_curve_shape() {
+        // A linear launch that opens at 10× the constant-product price
+        let linear = Linear { start_price: 10 * LAUNCH_PRICE, end_price: 100 * LAUNCH_PRICE };
+        let launch = CurveState::initial();
+        let fdv = fully_diluted_market_cap(&linear, &launch).unwrap();
+        let reserves_fdv = market_cap_lamports(launch.virtual_sol_reserve, launch.virtual_token_reserve, TOKEN_TOTAL_SUPPLY).unwrap();
+        assert!(fdv > 9 * reserves_fdv, "priced by the shape, not the virtual reserves");
+        // 10 × LAUNCH_PRICE / PRICE_SCALE lamports per base unit, less Q64.64 rounding
+        let nominal = (10 * LAUNCH_PRICE) as u128 * TOKEN_TOTAL_SUPPLY as u128 / PRICE_SCALE;
+        assert!(nominal - fdv as u128 <= 1);
+
+        let state = execute_buy(&linear, &launch, 10_000_000_000, PLATFORM_FEE_BPS, GRADUATION_THRESHOLD).unwrap().state;
+        let price = linear.spot_price_q64(&state).unwrap();
+        assert_eq!(circulating_market_cap(&linear, &state), Some(((price * state.tokens_sold as u128) >> 64) as u64));
+        assert!(fully_diluted_market_cap(&linear, &state).unwrap() > fdv, "price rose with the buy");
+    }
+
+    #[test]
+    fn test_market_cap_overflow_returns_none() {
+        assert_eq!(market_cap_lamports(u64::MAX, 1, 2), None);
+        assert_eq!(market_cap_lamports(1, 0, 1), None);
+    }
+
+    #[test]
+    fn test_f64_wrappers_match_fixed_point() {
+        let (vsr, vtr) = (INITIAL_VIRTUAL_SOL_RESERVE, INITIAL_VIRTUAL_TOKEN_RESERVE);
+        let naive = (vsr as f64 / 1e9) / (vtr as f64 / 1e6);
+        assert!((current_price_per_token(vsr, vtr) - naive).abs() < 1e-15);
+
+        let fdv = fully_diluted_market_cap(&ConstantProduct, &CurveState::initial()).unwrap();
+        assert_eq!(market_cap_sol(vsr, vtr, TOKEN_TOTAL_SUPPLY), fdv as f64 / 1e9);
+        assert_eq!(current_price_per_token(1, 0), 0.0);
+    }
+
+    // ─── 21. Graduation planning ──────────────────────────────────────────
+
+    // Helper: a constant-product curve that has raised `raised` lamports
+    fn raised(raised: u64) -> CurveState {
+        let mut state = CurveState::initial();
+        let tokens = calculate_tokens_out(state.virtual_sol_reserve, state.virtual_token_reserve, raised).unwrap();
+        state = state.after_buy(raised, tokens).unwrap();
+        state
+    }
+
+    // Helper: whether a curve state counts as complete (BondingCurve::is_complete)
+    fn complete(state: &CurveState, grad_threshold: u64) -> bool {
+        state.real_sol_balance >= grad_threshold || state.real_token_reserve == 0
+    }
+
+    // Every lamport offset around the threshold, from a few short to past it
+    const BOUNDARY: [u64; 6] = [
+        GRADUATION_THRESHOLD - 100, GRADUATION_THRESHOLD - 2, GRADUATION_THRESHOLD - 1,
+        GRADUATION_THRESHOLD, GRADUATION_THRESHOLD + 1, GRADUATION_THRESHOLD + 100,
+    ];
+
+    #[test]
+    fn test_sol_needed_to_graduate_at_threshold() {
+        for r in BOUNDARY {
+            let state = raised(r);
+            let needed = sol_needed_to_graduate(&ConstantProduct, &state, GRADUATION_THRESHOLD, 0).unwrap();
+            assert_eq!(needed, GRADUATION_THRESHOLD.saturating_sub(r), "raised {}", r);
+        }
+    }
+
+    #[test]
+    fn test_sol_needed_buy_completes_and_one_less_does_not() {
+        for r in BOUNDARY.into_iter().filter(|&r| r < GRADUATION_THRESHOLD) {
+            for fee_bps in [0, PLATFORM_FEE_BPS, 250] {
+                let state  = raised(r);
+                let needed = sol_needed_to_graduate(&ConstantProduct, &state, GRADUATION_THRESHOLD, fee_bps).unwrap();
+
+                let full = execute_buy(&ConstantProduct, &state, needed, fee_bps, GRADUATION_THRESHOLD).unwrap();
+                assert!(complete(&full.state, GRADUATION_THRESHOLD), "raised {} fee {}: needed buy fell short", r, fee_bps);
+                assert_eq!(full.sol_amount, needed, "needed buy should not be refunded anything");
+
+                if let Ok(short) = execute_buy(&ConstantProduct, &state, needed - 1, fee_bps, GRADUATION_THRESHOLD) {
+                    assert!(!complete(&short.state, GRADUATION_THRESHOLD), "raised {} fee {}: needed is not minimal", r, fee_bps);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sol_needed_when_reserve_sells_out_first() {
+        // Threshold out of reach: the reserve running out completes the curve
+        let state  = nearly_sold_out(1_000_000);
+        let needed = sol_needed_to_graduate(&ConstantProduct, &state, u64::MAX, PLATFORM_FEE_BPS).unwrap();
+        let cost   = calculate_sol_in(state.virtual_sol_reserve, state.virtual_token_reserve, 1_000_000).unwrap();
+        assert_eq!(needed, gross_up_for_fee(cost, PLATFORM_FEE_BPS).unwrap());
+
+        let full = execute_buy(&ConstantProduct, &state, needed, PLATFORM_FEE_BPS, u64::MAX).unwrap();
+        assert_eq!(full.state.real_token_reserve, 0);
+        let short = execute_buy(&ConstantProduct, &state, needed - 1, PLATFORM_FEE_BPS, u64::MAX).unwrap();
+        assert!(short.state.real_token_reserve > 0);
+
+        assert_eq!(sol_needed_to_graduate(&ConstantProduct, &nearly_sold_out(0), u64::MAX, PLATFORM_FEE_BPS), Some(0));
+    }
+
+    #[test]
+    fn test_tokens_remaining_at_threshold() {
+        // The default curve hits 85 SOL with a sliver of the reserve unsold
+        let launch = tokens_remaining(&ConstantProduct, &CurveState::initial(), GRADUATION_THRESHOLD).unwrap();
+        assert!(launch < INITIAL_REAL_TOKEN_RESERVE);
+        assert_eq!(launch, calculate_tokens_out(INITIAL_VIRTUAL_SOL_RESERVE, INITIAL_VIRTUAL_TOKEN_RESERVE, GRADUATION_THRESHOLD).unwrap());
+
+        for r in BOUNDARY {
+            let state     = raised(r);
+            let remaining = tokens_remaining(&ConstantProduct, &state, GRADUATION_THRESHOLD).unwrap();
+            if r >= GRADUATION_THRESHOLD {
+                assert_eq!(remaining, 0, "raised {}", r);
+                continue;
+            }
+            let room = GRADUATION_THRESHOLD - r;
+            assert_eq!(remaining, calculate_tokens_out(state.virtual_sol_reserve, state.virtual_token_reserve, room).unwrap());
+            assert!(remaining > 0, "raised {}: a lamport still buys something", r);
+
+            // The graduating buy delivers exactly what was promised
+            let needed = sol_needed_to_graduate(&ConstantProduct, &state, GRADUATION_THRESHOLD, PLATFORM_FEE_BPS).unwrap();
+            let buy    = execute_buy(&ConstantProduct, &state, needed, PLATFORM_FEE_BPS, GRADUATION_THRESHOLD).unwrap();
+            assert_eq!(buy.token_amount, remaining, "raised {}", r);
+        }
+    }
+
+    #[test]
+    fn test_tokens_remaining_when_reserve_sells_out_first() {
+        assert_eq!(tokens_remaining(&ConstantProduct, &nearly_sold_out(1_000_000), u64::MAX), Some(1_000_000));
+        assert_eq!(tokens_remaining(&ConstantProduct, &nearly_sold_out(0), u64::MAX), Some(0));
+    }
+
+    #[test]
+    fn test_graduation_progress_bps_at_threshold() {
+        assert_eq!(graduation_progress_bps(&CurveState::initial(), GRADUATION_THRESHOLD), 0);
+        assert_eq!(graduation_progress_bps(&raised(GRADUATION_THRESHOLD / 2), GRADUATION_THRESHOLD), 5_000);
+
+        let expected = [9_999, 9_999, 9_999, 10_000, 10_000, 10_000];
+        for (r, bps) in BOUNDARY.into_iter().zip(expected) {
+            assert_eq!(graduation_progress_bps(&raised(r), GRADUATION_THRESHOLD), bps, "raised {}", r);
+        }
+
+        // Sold out below the threshold is still complete
+        assert_eq!(graduation_progress_bps(&nearly_sold_out(0), u64::MAX), 10_000);
+        assert_eq!(graduation_progress_bps(&CurveState::initial(), 0), 10_000);
+    }
+
+    #[test]
+    fn test_sol_in_for_tokens_out_at_threshold() {
+        for r in BOUNDARY {
+            let state = raised(r);
+            for tokens in [1, 1_000_000, state.real_token_reserve] {
+                let sol = sol_in_for_tokens_out(&ConstantProduct, &state, tokens, PLATFORM_FEE_BPS).unwrap();
+                let outcome = execute_buy_exact_tokens(&ConstantProduct, &state, tokens, PLATFORM_FEE_BPS).unwrap();
+                assert_eq!(sol, outcome.sol_amount, "raised {} tokens {}", r, tokens);
+                // Spending that much through a plain buy gets at least as many tokens
+                let net = sol - fee_for(sol, PLATFORM_FEE_BPS).unwrap();
+                assert!(calculate_tokens_out(state.virtual_sol_reserve, state.virtual_token_reserve, net).unwrap() >= tokens);
+            }
+            assert_eq!(sol_in_for_tokens_out(&ConstantProduct, &state, state.real_token_reserve + 1, PLATFORM_FEE_BPS), None);
+            assert_eq!(sol_in_for_tokens_out(&ConstantProduct, &state, 0, PLATFORM_FEE_BPS), None);
+        }
+    }
+
+    #[test]
+    fn test_tokens_in_for_sol_out_at_threshold() {
+        for r in BOUNDARY {
+            let state = raised(r);
+            // Largest payout the raised SOL can cover after the fee. Draining it
+            // to the lamport can need more tokens than were sold (rounding
+            // dust stays in the pool), so plan up to just short of it.
+            let max_out = r - fee_for(r, PLATFORM_FEE_BPS).unwrap();
+
+            for sol_out in [1, 1_000_000_000, max_out - 1_000_000] {
+                let tokens = tokens_in_for_sol_out(&ConstantProduct, &state, sol_out, PLATFORM_FEE_BPS).unwrap();
+                assert!(tokens <= state.tokens_sold, "raised {} sol_out {}", r, sol_out);
+                let paid = execute_sell(&ConstantProduct, &state, tokens, PLATFORM_FEE_BPS).unwrap();
+                assert!(paid.sol_net >= sol_out, "raised {} sol_out {}: seller short-changed", r, sol_out);
+            }
+            assert_eq!(tokens_in_for_sol_out(&ConstantProduct, &state, max_out + 1, PLATFORM_FEE_BPS), None);
+            assert_eq!(tokens_in_for_sol_out(&ConstantProduct, &state, 0, PLATFORM_FEE_BPS), None);
+        }
+    }
+
+    #[test]
+    fn test_every_shape_plans_to_its_threshold() {
+        for (name, shape) in test_shapes() {
+            let state = execute_buy(shape.as_ref(), &CurveState::initial(), 10_000_000_000, 0, u64::MAX).unwrap().state;
+            let r = state.real_sol_balance;
+
+            for threshold in [r - 1, r, r + 1, r + 1_000_000_000] {
+                let needed    = sol_needed_to_graduate(shape.as_ref(), &state, threshold, PLATFORM_FEE_BPS).unwrap();
+                let remaining = tokens_remaining(shape.as_ref(), &state, threshold).unwrap();
+                let progress  = graduation_progress_bps(&state, threshold);
+
+                if threshold <= r {
+                    assert_eq!((needed, remaining, progress), (0, 0, 10_000), "{}: threshold {}", name, threshold);
+                    continue;
+                }
+                assert!(progress < 10_000, "{}", name);
+                let buy = execute_buy(shape.as_ref(), &state, needed, PLATFORM_FEE_BPS, threshold).unwrap();
+                assert!(complete(&buy.state, threshold), "{}: threshold {}", name, threshold);
+                assert_eq!(buy.token_amount, remaining, "{}: threshold {}", name, threshold);
+            }
+        }
+    }
+
+    // ─── 22. TWAP ─────────────────────────────────────────────────────────
+
+    // Helper: an accumulator driven the way buy / sell drive it — accrue the
+    // old price up to the trade's timestamp, then switch to the new price
+    struct Accumulator {
+        observation: PriceObservation,
+        price: u128,
+    }
+
+    impl Accumulator {
+        fn new(price: u128) -> Self {
+            Accumulator { observation: PriceObservation { price_cumulative: 0, timestamp: 0 }, price }
+        }
+
+        fn trade(&mut self, now: i64, new_price: u128) {
+            self.observation = self.observation.advance(self.price, now);
+            self.price = new_price;
+        }
+
+        fn at(&self, now: i64) -> PriceObservation {
+            self.observation.advance(self.price, now)
+        }
+    }
+
+    #[test]
+    fn test_twap_of_a_constant_price_is_that_price() {
+        let p = ConstantProduct.spot_price_q64(&CurveState::initial()).unwrap();
+        let acc = Accumulator::new(p);
+        let start = acc.at(0);
+        assert_eq!(twap(&start, &acc.at(3_600)), Some(p));
+        assert_eq!(twap(&acc.at(100), &acc.at(101)), Some(p));
+    }
+
+    #[test]
+    fn test_twap_weights_prices_by_time() {
+        // 1 for 30 minutes, then 3 for 30 minutes → 2
+        let mut acc = Accumulator::new(Q64_ONE);
+        let start = acc.at(0);
+        acc.trade(1_800, 3 * Q64_ONE);
+        assert_eq!(twap(&start, &acc.at(3_600)), Some(2 * Q64_ONE));
+    }
+
+    #[test]
+    fn test_same_second_spike_does_not_move_twap() {
+        // Whale pumps the price 10× and dumps in the same second
+        let p = ConstantProduct.spot_price_q64(&CurveState::initial()).unwrap();
+        let mut acc = Accumulator::new(p);
+        let start = acc.at(0);
+        acc.trade(1_000, 10 * p);
+        acc.trade(1_000, p);
+        assert_eq!(twap(&start, &acc.at(3_600)), Some(p));
+    }
+
+    #[test]
+    fn test_single_slot_spike_barely_moves_twap() {
+        // Real trades: push a constant-product curve up with a 60 SOL buy,
+        // hold it for one second, then sell everything back
+        let state = CurveState::initial();
+        let pumped = execute_buy(&ConstantProduct, &state, 60_000_000_000, 0, u64::MAX).unwrap();
+        let dumped = execute_sell(&ConstantProduct, &pumped.state, pumped.token_amount, 0).unwrap();
+
+        let p_before = ConstantProduct.spot_price_q64(&state).unwrap();
+        let p_spike  = ConstantProduct.spot_price_q64(&pumped.state).unwrap();
+        let p_after  = ConstantProduct.spot_price_q64(&dumped.state).unwrap();
+
+        let mut acc = Accumulator::new(p_before);
+        let start = acc.at(0);
+        acc.trade(1_800, p_spike);
+        acc.trade(1_801, p_after);
+        let avg = twap(&start, &acc.at(3_600)).unwrap();
+
+        // Spot went up ~9× (+80000 bps); a one-hour TWAP moves by under 0.3%
+        let spot_moved_bps = (p_spike - p_before) * BPS_DENOMINATOR as u128 / p_before;
+        assert!(spot_moved_bps >= 79_900, "spot moved {} bps", spot_moved_bps);
+        let moved_bps = (avg - p_before) * BPS_DENOMINATOR as u128 / p_before;
+        assert!(moved_bps < 30, "TWAP moved {} bps", moved_bps);
+    }
+
+    #[test]
+    fn test_twap_survives_accumulator_wraparound() {
+        let start = PriceObservation { price_cumulative: u128::MAX - 500, timestamp: 10 };
+        let end = start.advance(100, 20);
+        assert!(end.price_cumulative < start.price_cumulative, "accumulator should have wrapped");
+        assert_eq!(twap(&start, &end), Some(100));
+    }
+
+    #[test]
+    fn test_q64_spot_price_matches_the_scaled_one() {
+        // Constant product prices straight from the reserves; the other
+        // shapes convert their PRICE_SCALE price, losing under 2^64 / 1e12
+        let state = CurveState::initial();
+        assert_eq!(
+            ConstantProduct.spot_price_q64(&state),
+            price_q64(state.virtual_sol_reserve, state.virtual_token_reserve)
+        );
+        let linear = Linear { start_price: LAUNCH_PRICE, end_price: 400_000_000 };
+        let q64 = linear.spot_price_q64(&state).unwrap();
+        assert!(q64 * PRICE_SCALE <= LAUNCH_PRICE as u128 * Q64_ONE, "rounds down");
+        assert!(LAUNCH_PRICE as u128 * Q64_ONE - q64 * PRICE_SCALE < PRICE_SCALE);
+        assert_eq!(scaled_price_to_q64(PRICE_SCALE), Some(Q64_ONE));
+        assert_eq!(scaled_price_to_q64(u64::MAX as u128 * PRICE_SCALE), Some((u64::MAX as u128) << 64));
+        assert_eq!(scaled_price_to_q64(Q64_ONE * PRICE_SCALE), None);
+    }
+
+    #[test]
+    fn test_twap_needs_time_to_pass() {
+        let obs = PriceObservation { price_cumulative: 5, timestamp: 10 };
+        assert_eq!(twap(&obs, &obs), None);
+        assert_eq!(twap(&obs.advance(1, 20), &obs), None);
+        // A clock running backwards accrues nothing
+        assert_eq!(obs.advance(1_000, 5), obs);
+    }
+}
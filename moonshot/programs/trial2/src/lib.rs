@@ -1,23 +1,24 @@
 pub mod curve;
 pub use curve::*;
+pub mod amm;
+pub use amm::*;
+pub mod math;
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo, Transfer};
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, MintTo, SetAuthority, Transfer};
+use anchor_spl::token::spl_token::instruction::AuthorityType;
 use anchor_spl::associated_token::AssociatedToken;
 
 
 declare_id!("6gvL3BnHHw3a3HoQpmhcbuuVV2tHgnaUBcFjy3Tye55y");
 
-pub const PLATFORM_FEE_BPS: u64 = 100;
-pub const BPS_DENOMINATOR: u64 = 10_000;
-pub const GRADUATION_THRESHOLD_LAMPORTS: u64 = 85 * 1_000_000_000; //85 sol
-pub const INITIAL_VIRTUAL_SOL_RESERVE: u64 = 30 * 1_000_000_000; // 30 SOL
-pub const INITIAL_VIRTUAL_TOKEN_RESERVE: u64 = 1_073_000_000_000_000; // 1.073B tokens (6 decimals)
-pub const INITIAL_REAL_TOKEN_RESERVE: u64 = 793_100_000_000_000;
+// Pricing constants (fees, reserves, graduation threshold) live in curve.rs
 pub const TOKEN_DECIMALS: u8 = 6; //6 decimal places
 pub const MAX_NAME_LEN: usize = 32;
 pub const MAX_SYMBOL_LEN: usize = 10;
 pub const MAX_URI_LEN: usize = 200;
+/// Most update_platform_config will set the token creation fee to — 1 SOL
+pub const MAX_CREATION_FEE_LAMPORTS: u64 = 1_000_000_000;
 
 #[program]
 pub mod smooth {
@@ -28,6 +29,8 @@ pub mod smooth {
         fee_bps: u64,
         graduation_threshold: u64,
     ) -> Result<()> {
+        require!(fee_bps <= BPS_DENOMINATOR, ErrorCode::InvalidFeeBps);
+
         let config = &mut ctx.accounts.platform_config;
 
         config.admin = ctx.accounts.admin.key();
@@ -35,8 +38,31 @@ pub mod smooth {
         config.fee_wallet = ctx.accounts.fee_wallet.key();
         config.grad_threshold = graduation_threshold;
         config.total_tokens = 0;
+        config.pending_admin = None;
+        config.paused = false;
+        config.creator_fee_share_bps = 0;
+        config.referral_fee_share_bps = 0;
+        config.total_fees_collected = 0;
+        config.launch_fee_bps = 0;
+        config.launch_fee_duration = 0;
+        config.launch_fee_unit = LaunchFeeUnit::Seconds;
+        config.creation_fee_lamports = 0;
+        config.launch_bounds = LaunchBounds::defaults_only();
         config.bump = ctx.bumps.platform_config;
 
+        // Fund the fee vault up to the rent-exempt minimum so fee deposits of
+        // any size can land in it; withdrawals never dip below this floor
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: ctx.accounts.fee_vault.to_account_info(),
+                },
+            ),
+            Rent::get()?.minimum_balance(0),
+        )?;
+
         emit!(PlatformInitialized {
             admin: config.admin,
             fee_bps,
@@ -46,16 +72,209 @@ pub mod smooth {
         Ok(())
     }
 
+    pub fn update_platform_config(
+        ctx: Context<UpdatePlatformConfig>,
+        new_fee_bps: Option<u64>,
+        new_fee_wallet: Option<Pubkey>,
+        new_grad_threshold: Option<u64>,
+        new_creator_fee_share_bps: Option<u64>,
+        new_referral_fee_share_bps: Option<u64>,
+        new_creation_fee_lamports: Option<u64>,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.platform_config;
+
+        let old_fee_bps = config.fee_bps;
+        let old_fee_wallet = config.fee_wallet;
+        let old_grad_threshold = config.grad_threshold;
+        let old_creator_fee_share_bps = config.creator_fee_share_bps;
+        let old_referral_fee_share_bps = config.referral_fee_share_bps;
+        let old_creation_fee_lamports = config.creation_fee_lamports;
+
+        // Each field is optional — None leaves the current value untouched
+        if let Some(fee_bps) = new_fee_bps {
+            require!(fee_bps <= BPS_DENOMINATOR, ErrorCode::InvalidFeeBps);
+            config.fee_bps = fee_bps;
+        }
+        if let Some(fee_wallet) = new_fee_wallet {
+            config.fee_wallet = fee_wallet;
+        }
+        if let Some(grad_threshold) = new_grad_threshold {
+            require!(grad_threshold > 0, ErrorCode::InvalidGraduationThreshold);
+            config.grad_threshold = grad_threshold;
+        }
+        if let Some(share_bps) = new_creator_fee_share_bps {
+            require!(share_bps <= BPS_DENOMINATOR, ErrorCode::InvalidFeeBps);
+            config.creator_fee_share_bps = share_bps;
+        }
+        if let Some(share_bps) = new_referral_fee_share_bps {
+            require!(share_bps <= BPS_DENOMINATOR, ErrorCode::InvalidFeeBps);
+            config.referral_fee_share_bps = share_bps;
+        }
+        if let Some(creation_fee) = new_creation_fee_lamports {
+            require!(creation_fee <= MAX_CREATION_FEE_LAMPORTS, ErrorCode::InvalidCreationFee);
+            config.creation_fee_lamports = creation_fee;
+        }
+        // Creator and referrer are both paid out of the same fee
+        require!(
+            config.creator_fee_share_bps.saturating_add(config.referral_fee_share_bps) <= BPS_DENOMINATOR,
+            ErrorCode::InvalidFeeBps
+        );
+
+        emit!(PlatformConfigUpdated {
+            admin: ctx.accounts.admin.key(),
+            old_fee_bps,
+            new_fee_bps: config.fee_bps,
+            old_fee_wallet,
+            new_fee_wallet: config.fee_wallet,
+            old_grad_threshold,
+            new_grad_threshold: config.grad_threshold,
+            old_creator_fee_share_bps,
+            new_creator_fee_share_bps: config.creator_fee_share_bps,
+            old_referral_fee_share_bps,
+            new_referral_fee_share_bps: config.referral_fee_share_bps,
+            old_creation_fee_lamports,
+            new_creation_fee_lamports: config.creation_fee_lamports,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.platform_config;
+        config.pending_admin = Some(new_admin);
+
+        emit!(AdminTransferProposed {
+            admin: config.admin,
+            pending_admin: new_admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.platform_config;
+        let old_admin = config.admin;
+
+        // The incoming admin has signed, so the key is known to be usable
+        config.admin = ctx.accounts.new_admin.key();
+        config.pending_admin = None;
+
+        emit!(AdminTransferAccepted {
+            old_admin,
+            new_admin: config.admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+        let config = &mut ctx.accounts.platform_config;
+        let cancelled = config.pending_admin.take().ok_or(ErrorCode::NoPendingAdmin)?;
+
+        emit!(AdminTransferCancelled {
+            admin: config.admin,
+            cancelled_admin: cancelled,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_launch_bounds(ctx: Context<SetLaunchBounds>, bounds: LaunchBounds) -> Result<()> {
+        require!(bounds.is_consistent(), ErrorCode::InvalidLaunchParams);
+
+        let config = &mut ctx.accounts.platform_config;
+        config.launch_bounds = bounds;
+
+        emit!(LaunchBoundsUpdated {
+            admin: config.admin,
+            bounds,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_launch_fee_schedule(
+        ctx: Context<SetLaunchFeeSchedule>,
+        launch_fee_bps: u64,
+        duration: u64,
+        unit: LaunchFeeUnit,
+    ) -> Result<()> {
+        // duration = 0 turns the schedule off
+        require!(launch_fee_bps <= BPS_DENOMINATOR, ErrorCode::InvalidFeeBps);
+
+        let config = &mut ctx.accounts.platform_config;
+        config.launch_fee_bps = launch_fee_bps;
+        config.launch_fee_duration = duration;
+        config.launch_fee_unit = unit;
+
+        emit!(LaunchFeeScheduleUpdated {
+            admin: config.admin,
+            launch_fee_bps,
+            duration,
+            unit,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_platform_paused(ctx: Context<SetPlatformPaused>, paused: bool) -> Result<()> {
+        let config = &mut ctx.accounts.platform_config;
+        config.paused = paused;
+
+        emit!(PlatformPauseToggled {
+            admin: config.admin,
+            paused,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_token_paused(ctx: Context<SetTokenPaused>, paused: bool) -> Result<()> {
+        let curve = &mut ctx.accounts.bonding_curve;
+        curve.is_paused = paused;
+
+        emit!(TokenPauseToggled {
+            mint: ctx.accounts.mint.key(),
+            admin: ctx.accounts.admin.key(),
+            paused,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
     pub fn create_token(
         ctx: Context<CreateToken>,
         name: String,
         symbol: String,
         uri: String,
         description: String,
+        curve_kind: CurveKind,
+        launch_params: LaunchParams,
     ) -> Result<()> {
-        require!(name.len() > 0 && name.len() <= MAX_NAME_LEN, ErrorCode::InvalidName);
-        require!(symbol.len() > 0 && symbol.len() <= MAX_SYMBOL_LEN, ErrorCode::InvalidSymbol);
-        require!(uri.len() > 0 && uri.len() <= MAX_URI_LEN, ErrorCode::InvalidUri);
+        require!(!ctx.accounts.platform_config.paused, ErrorCode::Paused);
+        require!(!name.is_empty() && name.len() <= MAX_NAME_LEN, ErrorCode::InvalidName);
+        require!(!symbol.is_empty() && symbol.len() <= MAX_SYMBOL_LEN, ErrorCode::InvalidSymbol);
+        require!(!uri.is_empty() && uri.len() <= MAX_URI_LEN, ErrorCode::InvalidUri);
+        require!(curve_kind.is_valid(), ErrorCode::InvalidCurveParams);
+
+        let terms = LaunchTerms::new(&ctx.accounts.platform_config, curve_kind, &launch_params);
+        let LaunchTerms { virtual_sol_reserve, virtual_token_reserve, real_token_reserve, .. } = terms;
+        require!(
+            ctx.accounts.platform_config.launch_bounds.allows(
+                virtual_sol_reserve,
+                virtual_token_reserve,
+                real_token_reserve,
+            ),
+            ErrorCode::InvalidLaunchParams
+        );
 
         let clock = Clock::get()?;
 
@@ -71,16 +290,14 @@ pub mod smooth {
         meta.bump = ctx.bumps.token_meta;
 
         // Initialize bonding curve
-        let curve = &mut ctx.accounts.bonding_curve;
-        curve.mint = ctx.accounts.mint.key();
-        curve.creator = ctx.accounts.creator.key();
-        curve.virtual_sol_reserve = INITIAL_VIRTUAL_SOL_RESERVE;
-        curve.virtual_token_reserve = INITIAL_VIRTUAL_TOKEN_RESERVE;
-        curve.real_sol_balance = 0;
-        curve.real_token_reserve = INITIAL_REAL_TOKEN_RESERVE;
-        curve.token_total_supply = 0;
-        curve.is_graduated = false;
-        curve.bump = ctx.bumps.bonding_curve;
+        ctx.accounts.bonding_curve.set_inner(BondingCurve::launch(
+            ctx.accounts.mint.key(),
+            ctx.accounts.creator.key(),
+            &terms,
+            &clock,
+            ctx.bumps.bonding_curve,
+        ));
+        let curve = &ctx.accounts.bonding_curve;
 
         // Mint initial token supply into bonding curve token account
         // Store the mint key in a variable to extend its lifetime
@@ -101,10 +318,54 @@ token::mint_to(
         },
         mint_seeds,
     ),
-    INITIAL_REAL_TOKEN_RESERVE,
+    real_token_reserve,
 )?;
 
-        ctx.accounts.platform_config.total_tokens += 1;
+        // Fund the creator vault up to the rent-exempt minimum so small fee
+        // deposits can land in it; claims never dip below this floor
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: ctx.accounts.creator_vault.to_account_info(),
+                },
+            ),
+            Rent::get()?.minimum_balance(0),
+        )?;
+
+        // Same for the SOL vault, so the curve's reserve sits on top of the
+        // rent floor and real_sol_balance can be checked against it
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: ctx.accounts.sol_vault.to_account_info(),
+                },
+            ),
+            Rent::get()?.minimum_balance(0),
+        )?;
+
+        // Spam deterrent — goes to the fee vault like trading fees
+        let creation_fee = ctx.accounts.platform_config.creation_fee_lamports;
+        if creation_fee > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.creator.to_account_info(),
+                        to: ctx.accounts.fee_vault.to_account_info(),
+                    },
+                ),
+                creation_fee,
+            )?;
+        }
+
+        let config = &mut ctx.accounts.platform_config;
+        config.total_tokens = math::add(config.total_tokens, 1, "total_tokens")?;
+        config.total_fees_collected =
+            math::add(config.total_fees_collected, creation_fee, "total_fees_collected")?;
 
         emit!(TokenCreated {
             mint: ctx.accounts.mint.key(),
@@ -112,6 +373,10 @@ token::mint_to(
             name,
             symbol,
             uri,
+            creation_fee,
+            virtual_sol_reserve,
+            virtual_token_reserve,
+            real_token_reserve,
             timestamp: clock.unix_timestamp,
         });
 
@@ -124,122 +389,53 @@ token::mint_to(
         min_tokens_out: u64,
     ) -> Result<()> {
         let curve = &ctx.accounts.bonding_curve;
-        require!(!curve.is_graduated, ErrorCode::TokenGraduated);
-        require!(sol_amount > 0, ErrorCode::ZeroAmount);
-
-        let fee_lamports = sol_amount
-            .checked_mul(ctx.accounts.platform_config.fee_bps)
-            .unwrap()
-            .checked_div(BPS_DENOMINATOR)
-            .unwrap();
-
-        let sol_for_curve = sol_amount.checked_sub(fee_lamports).unwrap();
-
-        let tokens_out = {
-            let curve = &ctx.accounts.bonding_curve;
-            let new_sol_reserve = curve
-                .virtual_sol_reserve
-                .checked_add(sol_for_curve)
-                .unwrap();
-            let k = (curve.virtual_sol_reserve as u128)
-                .checked_mul(curve.virtual_token_reserve as u128)
-                .unwrap();
-            let new_token_reserve = k.checked_div(new_sol_reserve as u128).unwrap() as u64;
-            curve
-                .virtual_token_reserve
-                .checked_sub(new_token_reserve)
-                .unwrap()
-        };
-
-        require!(tokens_out >= min_tokens_out, ErrorCode::SlippageExceeded);
-        require!(tokens_out > 0, ErrorCode::ZeroAmount);
-
         require!(
-            ctx.accounts.bonding_curve_token_account.amount >= tokens_out,
-            ErrorCode::InsufficientTokens
+            !ctx.accounts.platform_config.paused && !curve.is_paused,
+            ErrorCode::Paused
         );
+        require!(!curve.is_graduated, ErrorCode::TokenGraduated);
 
-        // Transfer SOL: buyer → fee wallet
-        anchor_lang::system_program::transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                anchor_lang::system_program::Transfer {
-                    from: ctx.accounts.buyer.to_account_info(),
-                    to: ctx.accounts.fee_wallet.to_account_info(),
-                },
-            ),
-            fee_lamports,
+        // Snipers right after launch pay the decaying launch fee, not the base fee
+        let fee_bps = curve.current_fee_bps(&Clock::get()?);
+        let outcome = execute_buy(
+            &curve.curve_kind,
+            &curve.state(),
+            sol_amount,
+            fee_bps,
+            curve.grad_threshold,
         )?;
 
-        // Transfer SOL: buyer → bonding curve PDA
-        anchor_lang::system_program::transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                anchor_lang::system_program::Transfer {
-                    from: ctx.accounts.buyer.to_account_info(),
-                    to: ctx.accounts.bonding_curve.to_account_info(),
-                },
-            ),
-            sol_for_curve,
-        )?;
+        // The completing buy may fill only part of sol_amount — hold that
+        // part to the quoted price rather than the quoted size
+        let sol_offered = math::sub(sol_amount, fee_for(sol_amount, fee_bps)?, "sol_offered")?;
+        require!(
+            outcome.token_amount >= min_tokens_for_fill(min_tokens_out, sol_offered, outcome.sol_net),
+            ErrorCode::SlippageExceeded
+        );
 
-        // Transfer tokens: bonding curve → buyer
-        let curve_bump = ctx.accounts.bonding_curve.bump;
-        let mint_key = ctx.accounts.mint.key();
-        let signer_seeds: &[&[&[u8]]] = &[&[
-            b"bonding_curve",
-            mint_key.as_ref(),
-            &[curve_bump],
-        ]];
+        settle_buy(ctx.accounts, &ctx.bumps, &outcome)
+    }
 
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.bonding_curve_token_account.to_account_info(),
-                    to: ctx.accounts.buyer_token_account.to_account_info(),
-                    authority: ctx.accounts.bonding_curve.to_account_info(),
-                },
-                signer_seeds,
-            ),
-            tokens_out,
-        )?;
+    /// Buy exactly `token_amount` tokens, paying at most `max_sol_in` lamports
+    /// including the fee.
+    pub fn buy_exact_tokens(
+        ctx: Context<Buy>,
+        token_amount: u64,
+        max_sol_in: u64,
+    ) -> Result<()> {
+        let curve = &ctx.accounts.bonding_curve;
+        require!(
+            !ctx.accounts.platform_config.paused && !curve.is_paused,
+            ErrorCode::Paused
+        );
+        require!(!curve.is_graduated, ErrorCode::TokenGraduated);
 
-        let curve = &mut ctx.accounts.bonding_curve;
-        curve.virtual_sol_reserve = curve
-            .virtual_sol_reserve
-            .checked_add(sol_for_curve)
-            .unwrap();
-        curve.virtual_token_reserve = curve
-            .virtual_token_reserve
-            .checked_sub(tokens_out)
-            .unwrap();
-        curve.real_sol_balance = curve.real_sol_balance.checked_add(sol_for_curve).unwrap();
-        curve.real_token_reserve = curve.real_token_reserve.checked_sub(tokens_out).unwrap();
-        curve.token_total_supply = curve.token_total_supply.checked_add(tokens_out).unwrap();
-
-        if curve.real_sol_balance >= ctx.accounts.platform_config.grad_threshold {
-            curve.is_graduated = true;
-            emit!(TokenGraduated {
-                mint: ctx.accounts.mint.key(),
-                sol_raised: curve.real_sol_balance,
-                timestamp: Clock::get()?.unix_timestamp,
-            });
-        }
+        let fee_bps = curve.current_fee_bps(&Clock::get()?);
+        let outcome = execute_buy_exact_tokens(&curve.curve_kind, &curve.state(), token_amount, fee_bps)?;
 
-        emit!(TradeExecuted {
-            mint: ctx.accounts.mint.key(),
-            trader: ctx.accounts.buyer.key(),
-            is_buy: true,
-            sol_amount,
-            token_amount: tokens_out,
-            fee: fee_lamports,
-            timestamp: Clock::get()?.unix_timestamp,
-            virtual_sol_reserve: curve.virtual_sol_reserve,
-            virtual_token_reserve: curve.virtual_token_reserve,
-        });
+        require!(outcome.sol_amount <= max_sol_in, ErrorCode::SlippageExceeded);
 
-        Ok(())
+        settle_buy(ctx.accounts, &ctx.bumps, &outcome)
     }
 
     pub fn sell(
@@ -248,110 +444,135 @@ token::mint_to(
         min_sol_out: u64,
     ) -> Result<()> {
         let curve = &ctx.accounts.bonding_curve;
-        require!(!curve.is_graduated, ErrorCode::TokenGraduated);
-        require!(token_amount > 0, ErrorCode::ZeroAmount);
         require!(
-            ctx.accounts.seller_token_account.amount >= token_amount,
-            ErrorCode::InsufficientTokens
+            !ctx.accounts.platform_config.paused && !curve.is_paused,
+            ErrorCode::Paused
         );
+        require!(!curve.is_graduated, ErrorCode::TokenGraduated);
 
-        let sol_out = {
-            let new_token_reserve = curve
-                .virtual_token_reserve
-                .checked_add(token_amount)
-                .unwrap();
-            let k = (curve.virtual_sol_reserve as u128)
-                .checked_mul(curve.virtual_token_reserve as u128)
-                .unwrap();
-            let new_sol_reserve = k.checked_div(new_token_reserve as u128).unwrap() as u64;
-            curve
-                .virtual_sol_reserve
-                .checked_sub(new_sol_reserve)
-                .unwrap()
-        };
+        let outcome = execute_sell(&curve.curve_kind, &curve.state(), token_amount, curve.fee_bps)?;
 
-        let fee_lamports = sol_out
-            .checked_mul(ctx.accounts.platform_config.fee_bps)
-            .unwrap()
-            .checked_div(BPS_DENOMINATOR)
-            .unwrap();
+        require!(outcome.sol_net >= min_sol_out, ErrorCode::SlippageExceeded);
 
-        let sol_to_seller = sol_out.checked_sub(fee_lamports).unwrap();
+        settle_sell(ctx.accounts, &ctx.bumps, &outcome)
+    }
 
-        require!(sol_to_seller >= min_sol_out, ErrorCode::SlippageExceeded);
-        require!(sol_out > 0, ErrorCode::ZeroAmount);
+    /// Sell just enough tokens to receive exactly `sol_out` lamports after the
+    /// fee, spending at most `max_tokens_in`.
+    pub fn sell_for_exact_sol(
+        ctx: Context<Sell>,
+        sol_out: u64,
+        max_tokens_in: u64,
+    ) -> Result<()> {
+        let curve = &ctx.accounts.bonding_curve;
         require!(
-            curve.real_sol_balance >= sol_out,
-            ErrorCode::InsufficientSol
+            !ctx.accounts.platform_config.paused && !curve.is_paused,
+            ErrorCode::Paused
         );
+        require!(!curve.is_graduated, ErrorCode::TokenGraduated);
 
-        // Transfer tokens: seller → bonding curve
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.seller_token_account.to_account_info(),
-                    to: ctx.accounts.bonding_curve_token_account.to_account_info(),
-                    authority: ctx.accounts.seller.to_account_info(),
+        let outcome = execute_sell_for_exact_sol(&curve.curve_kind, &curve.state(), sol_out, curve.fee_bps)?;
+
+        require!(outcome.token_amount <= max_tokens_in, ErrorCode::SlippageExceeded);
+
+        settle_sell(ctx.accounts, &ctx.bumps, &outcome)
+    }
+
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        // Everything above the rent-exempt floor belongs to the creator
+        let rent_floor = Rent::get()?.minimum_balance(0);
+        let amount = ctx.accounts.creator_vault.lamports().saturating_sub(rent_floor);
+        require!(amount > 0, ErrorCode::ZeroAmount);
+
+        let mint_key = ctx.accounts.mint.key();
+        let vault_seeds: &[&[&[u8]]] = &[&[
+            b"creator_vault",
+            mint_key.as_ref(),
+            &[ctx.bumps.creator_vault],
+        ]];
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.creator_vault.to_account_info(),
+                    to: ctx.accounts.creator.to_account_info(),
                 },
+                vault_seeds,
             ),
-            token_amount,
+            amount,
         )?;
 
-        let curve_bump = ctx.accounts.bonding_curve.bump;
-        let mint_key = ctx.accounts.mint.key();
-        let signer_seeds: &[&[&[u8]]] = &[&[
-            b"bonding_curve",
-            mint_key.as_ref(),
-            &[curve_bump],
+        emit!(CreatorFeesClaimed {
+            mint: mint_key,
+            creator: ctx.accounts.creator.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+        // Everything above the rent-exempt floor belongs to the referrer
+        let rent_floor = Rent::get()?.minimum_balance(0);
+        let amount = ctx.accounts.referral_vault.lamports().saturating_sub(rent_floor);
+        require!(amount > 0, ErrorCode::ZeroAmount);
+
+        let referrer_key = ctx.accounts.referrer.key();
+        let vault_seeds: &[&[&[u8]]] = &[&[
+            b"referral_vault",
+            referrer_key.as_ref(),
+            &[ctx.bumps.referral_vault],
         ]];
 
-        // Transfer SOL: bonding curve → seller
         anchor_lang::system_program::transfer(
             CpiContext::new_with_signer(
                 ctx.accounts.system_program.to_account_info(),
                 anchor_lang::system_program::Transfer {
-                    from: ctx.accounts.bonding_curve.to_account_info(),
-                    to: ctx.accounts.seller.to_account_info(),
+                    from: ctx.accounts.referral_vault.to_account_info(),
+                    to: ctx.accounts.referrer.to_account_info(),
                 },
-                signer_seeds,
+                vault_seeds,
             ),
-            sol_to_seller,
+            amount,
         )?;
 
-        // Transfer fee: bonding curve → fee wallet
+        emit!(ReferralRewardsClaimed {
+            referrer: referrer_key,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::ZeroAmount);
+
+        let rent_floor = Rent::get()?.minimum_balance(0);
+        let available = ctx.accounts.fee_vault.lamports().saturating_sub(rent_floor);
+        require!(amount <= available, ErrorCode::InsufficientSol);
+
+        let vault_seeds: &[&[&[u8]]] = &[&[b"fee_vault", &[ctx.bumps.fee_vault]]];
+
         anchor_lang::system_program::transfer(
             CpiContext::new_with_signer(
                 ctx.accounts.system_program.to_account_info(),
                 anchor_lang::system_program::Transfer {
-                    from: ctx.accounts.bonding_curve.to_account_info(),
+                    from: ctx.accounts.fee_vault.to_account_info(),
                     to: ctx.accounts.fee_wallet.to_account_info(),
                 },
-                signer_seeds,
+                vault_seeds,
             ),
-            fee_lamports,
+            amount,
         )?;
 
-        let curve = &mut ctx.accounts.bonding_curve;
-        curve.virtual_sol_reserve = curve.virtual_sol_reserve.checked_sub(sol_out).unwrap();
-        curve.virtual_token_reserve = curve
-            .virtual_token_reserve
-            .checked_add(token_amount)
-            .unwrap();
-        curve.real_sol_balance = curve.real_sol_balance.checked_sub(sol_out).unwrap();
-        curve.real_token_reserve = curve.real_token_reserve.checked_add(token_amount).unwrap();
-        curve.token_total_supply = curve.token_total_supply.checked_sub(token_amount).unwrap();
-
-        emit!(TradeExecuted {
-            mint: ctx.accounts.mint.key(),
-            trader: ctx.accounts.seller.key(),
-            is_buy: false,
-            sol_amount: sol_out,
-            token_amount,
-            fee: fee_lamports,
+        emit!(FeesWithdrawn {
+            admin: ctx.accounts.admin.key(),
+            fee_wallet: ctx.accounts.fee_wallet.key(),
+            amount,
             timestamp: Clock::get()?.unix_timestamp,
-            virtual_sol_reserve: curve.virtual_sol_reserve,
-            virtual_token_reserve: curve.virtual_token_reserve,
         });
 
         Ok(())
@@ -359,10 +580,7 @@ token::mint_to(
 
     pub fn graduate(ctx: Context<Graduate>) -> Result<()> {
         let curve = &mut ctx.accounts.bonding_curve;
-        require!(
-            curve.real_sol_balance >= ctx.accounts.platform_config.grad_threshold,
-            ErrorCode::NotReadyToGraduate
-        );
+        require!(curve.is_complete(), ErrorCode::NotReadyToGraduate);
         require!(!curve.is_graduated, ErrorCode::AlreadyGraduated);
         
         curve.is_graduated = true;
@@ -375,79 +593,1658 @@ token::mint_to(
         
         Ok(())
     }
-}
 
-#[account]
-pub struct PlatformConfig {
-    pub admin: Pubkey,
-    pub fee_wallet: Pubkey,
-    pub fee_bps: u64,
-    pub grad_threshold: u64,
-    pub total_tokens: u64,
-    pub bump: u8,
-}
+    /// Move a graduated curve into its constant-product pool: the raised SOL,
+    /// plus every token not in holders' hands — the unsold ones, and the rest
+    /// of TOKEN_TOTAL_SUPPLY minted on top. Mint authority is revoked afterwards
+    /// and the opening liquidity goes to the lp_locker PDA, which never signs.
+    /// Anyone can call it, once per token.
+    pub fn migrate_to_pool(ctx: Context<MigrateToPool>) -> Result<()> {
+        let curve = &ctx.accounts.bonding_curve;
+        require!(curve.is_graduated, ErrorCode::NotGraduated);
+        require!(!curve.is_migrated, ErrorCode::AlreadyMigrated);
 
-impl PlatformConfig {
-    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1;
-}
+        let rent_floor = Rent::get()?.minimum_balance(0);
+        require!(
+            vault_covers_balance(curve.real_sol_balance, ctx.accounts.sol_vault.lamports(), rent_floor),
+            ErrorCode::VaultBalanceMismatch
+        );
 
-#[account]
-pub struct TokenMeta {
-    pub mint: Pubkey,
-    pub creator: Pubkey,
-    pub name: String,
-    pub symbol: String,
-    pub uri: String,
-    pub description: String,
-    pub created_at: i64,
-    pub bump: u8,
-}
+        let sol_amount = curve.real_sol_balance;
+        let unsold = ctx.accounts.bonding_curve_token_account.amount;
+        let top_up = math::checked(TOKEN_TOTAL_SUPPLY.checked_sub(ctx.accounts.mint.supply), "pool top_up")?;
+        let token_amount = math::add(unsold, top_up, "pool token_reserve")?;
+        let liquidity = initial_liquidity(sol_amount, token_amount);
+        require!(liquidity > 0, ErrorCode::ZeroAmount);
 
-impl TokenMeta {
-    pub const LEN: usize = 8 + 32 + 32 + (4 + MAX_NAME_LEN) + (4 + MAX_SYMBOL_LEN) + (4 + MAX_URI_LEN) + (4 + MAX_URI_LEN) + 8 + 1;
-}
+        let mint_key = ctx.accounts.mint.key();
+        let curve_seeds: &[&[&[u8]]] = &[&[b"bonding_curve", mint_key.as_ref(), &[curve.bump]]];
+        let vault_seeds: &[&[&[u8]]] = &[&[b"sol_vault", mint_key.as_ref(), &[ctx.bumps.sol_vault]]];
+        let pool_seeds: &[&[&[u8]]] = &[&[b"pool", mint_key.as_ref(), &[ctx.bumps.pool]]];
 
-#[account]
-pub struct BondingCurve {
-    pub mint: Pubkey,
-    pub creator: Pubkey,
+        // Fund the pool's SOL vault up to the rent floor, same as the curve's
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: ctx.accounts.pool_sol_vault.to_account_info(),
+                },
+            ),
+            rent_floor,
+        )?;
+
+        // Transfer SOL: curve's SOL vault → pool's SOL vault
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.sol_vault.to_account_info(),
+                    to: ctx.accounts.pool_sol_vault.to_account_info(),
+                },
+                vault_seeds,
+            ),
+            sol_amount,
+        )?;
+
+        // Transfer tokens: bonding curve → pool
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.bonding_curve_token_account.to_account_info(),
+                    to: ctx.accounts.pool_token_account.to_account_info(),
+                    authority: ctx.accounts.bonding_curve.to_account_info(),
+                },
+                curve_seeds,
+            ),
+            unsold,
+        )?;
+
+        // Mint the rest of the supply straight into the pool
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.pool_token_account.to_account_info(),
+                    authority: ctx.accounts.bonding_curve.to_account_info(),
+                },
+                curve_seeds,
+            ),
+            top_up,
+        )?;
+
+        // Supply is final from here on
+        token::set_authority(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                SetAuthority {
+                    current_authority: ctx.accounts.bonding_curve.to_account_info(),
+                    account_or_mint: ctx.accounts.mint.to_account_info(),
+                },
+                curve_seeds,
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )?;
+
+        // Opening LP to the locker: it's counted in the LP mint's supply like
+        // any other share, but nothing can sign to burn it
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: ctx.accounts.locked_lp_account.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                pool_seeds,
+            ),
+            liquidity,
+        )?;
+
+        // Everything the curve held now sits in the pool
+        let curve = &mut ctx.accounts.bonding_curve;
+        curve.real_sol_balance = 0;
+        curve.real_token_reserve = 0;
+        curve.is_migrated = true;
+
+        let clock = Clock::get()?;
+        let pool = &mut ctx.accounts.pool;
+        pool.mint = mint_key;
+        pool.lp_mint = ctx.accounts.lp_mint.key();
+        pool.sol_reserve = sol_amount;
+        pool.token_reserve = token_amount;
+        pool.lp_supply = liquidity;
+        pool.locked_lp = liquidity;
+        pool.fee_bps = POOL_FEE_BPS;
+        pool.created_at = clock.unix_timestamp;
+        pool.bump = ctx.bumps.pool;
+
+        emit!(PoolCreated {
+            mint: mint_key,
+            pool: pool.key(),
+            sol_reserve: sol_amount,
+            token_reserve: token_amount,
+            locked_lp: liquidity,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Trade against the pool. `amount_in` is lamports for SolToToken and
+    /// tokens for TokenToSol; the swap fee stays in the pool.
+    pub fn swap(
+        ctx: Context<Swap>,
+        amount_in: u64,
+        min_amount_out: u64,
+        direction: SwapDirection,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.platform_config.paused && !ctx.accounts.bonding_curve.is_paused,
+            ErrorCode::Paused
+        );
+
+        let pool = &ctx.accounts.pool;
+        let rent_floor = Rent::get()?.minimum_balance(0);
+        require!(
+            vault_covers_balance(pool.sol_reserve, ctx.accounts.pool_sol_vault.lamports(), rent_floor),
+            ErrorCode::VaultBalanceMismatch
+        );
+
+        let outcome = match direction {
+            SwapDirection::SolToToken => pool.state().swap_sol_for_tokens(amount_in, pool.fee_bps)?,
+            SwapDirection::TokenToSol => pool.state().swap_tokens_for_sol(amount_in, pool.fee_bps)?,
+        };
+        let amount_out = match direction {
+            SwapDirection::SolToToken => outcome.token_amount,
+            SwapDirection::TokenToSol => outcome.sol_amount,
+        };
+        require!(amount_out >= min_amount_out, ErrorCode::SlippageExceeded);
+
+        let mint_key = ctx.accounts.mint.key();
+        let pool_seeds: &[&[&[u8]]] = &[&[b"pool", mint_key.as_ref(), &[pool.bump]]];
+        let vault_seeds: &[&[&[u8]]] = &[&[b"pool_sol_vault", mint_key.as_ref(), &[ctx.bumps.pool_sol_vault]]];
+
+        match direction {
+            SwapDirection::SolToToken => {
+                // Transfer SOL: trader → pool
+                anchor_lang::system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        anchor_lang::system_program::Transfer {
+                            from: ctx.accounts.trader.to_account_info(),
+                            to: ctx.accounts.pool_sol_vault.to_account_info(),
+                        },
+                    ),
+                    outcome.sol_amount,
+                )?;
+
+                // Transfer tokens: pool → trader
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.pool_token_account.to_account_info(),
+                            to: ctx.accounts.trader_token_account.to_account_info(),
+                            authority: ctx.accounts.pool.to_account_info(),
+                        },
+                        pool_seeds,
+                    ),
+                    outcome.token_amount,
+                )?;
+            }
+            SwapDirection::TokenToSol => {
+                require!(
+                    ctx.accounts.trader_token_account.amount >= outcome.token_amount,
+                    ErrorCode::InsufficientTokens
+                );
+
+                // Transfer tokens: trader → pool
+                token::transfer(
+                    CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.trader_token_account.to_account_info(),
+                            to: ctx.accounts.pool_token_account.to_account_info(),
+                            authority: ctx.accounts.trader.to_account_info(),
+                        },
+                    ),
+                    outcome.token_amount,
+                )?;
+
+                // Transfer SOL: pool → trader
+                anchor_lang::system_program::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.system_program.to_account_info(),
+                        anchor_lang::system_program::Transfer {
+                            from: ctx.accounts.pool_sol_vault.to_account_info(),
+                            to: ctx.accounts.trader.to_account_info(),
+                        },
+                        vault_seeds,
+                    ),
+                    outcome.sol_amount,
+                )?;
+            }
+        }
+
+        let pool = &mut ctx.accounts.pool;
+        pool.set_state(&outcome.state);
+
+        emit!(PoolSwapped {
+            mint: mint_key,
+            trader: ctx.accounts.trader.key(),
+            is_buy: direction == SwapDirection::SolToToken,
+            sol_amount: outcome.sol_amount,
+            token_amount: outcome.token_amount,
+            sol_reserve: pool.sol_reserve,
+            token_reserve: pool.token_reserve,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Mint exactly `lp_amount` LP tokens, depositing SOL and tokens in the
+    /// pool's current ratio — at most `max_sol_in` and `max_tokens_in`.
+    pub fn add_liquidity(
+        ctx: Context<Liquidity>,
+        lp_amount: u64,
+        max_sol_in: u64,
+        max_tokens_in: u64,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.platform_config.paused && !ctx.accounts.bonding_curve.is_paused,
+            ErrorCode::Paused
+        );
+
+        let pool = &ctx.accounts.pool;
+        let outcome = pool.state().deposit(lp_amount)?;
+        require!(
+            outcome.sol_amount <= max_sol_in && outcome.token_amount <= max_tokens_in,
+            ErrorCode::SlippageExceeded
+        );
+        require!(
+            ctx.accounts.provider_token_account.amount >= outcome.token_amount,
+            ErrorCode::InsufficientTokens
+        );
+
+        let mint_key = ctx.accounts.mint.key();
+        let pool_seeds: &[&[&[u8]]] = &[&[b"pool", mint_key.as_ref(), &[pool.bump]]];
+
+        // Transfer SOL: provider → pool
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.provider.to_account_info(),
+                    to: ctx.accounts.pool_sol_vault.to_account_info(),
+                },
+            ),
+            outcome.sol_amount,
+        )?;
+
+        // Transfer tokens: provider → pool
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.provider_token_account.to_account_info(),
+                    to: ctx.accounts.pool_token_account.to_account_info(),
+                    authority: ctx.accounts.provider.to_account_info(),
+                },
+            ),
+            outcome.token_amount,
+        )?;
+
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: ctx.accounts.provider_lp_account.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                pool_seeds,
+            ),
+            lp_amount,
+        )?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.set_state(&outcome.state);
+
+        emit!(LiquidityAdded {
+            mint: mint_key,
+            provider: ctx.accounts.provider.key(),
+            lp_amount,
+            sol_amount: outcome.sol_amount,
+            token_amount: outcome.token_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Burn `lp_amount` LP tokens for their share of both reserves, receiving
+    /// at least `min_sol_out` and `min_tokens_out`.
+    pub fn remove_liquidity(
+        ctx: Context<Liquidity>,
+        lp_amount: u64,
+        min_sol_out: u64,
+        min_tokens_out: u64,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.platform_config.paused && !ctx.accounts.bonding_curve.is_paused,
+            ErrorCode::Paused
+        );
+
+        let pool = &ctx.accounts.pool;
+        let rent_floor = Rent::get()?.minimum_balance(0);
+        require!(
+            vault_covers_balance(pool.sol_reserve, ctx.accounts.pool_sol_vault.lamports(), rent_floor),
+            ErrorCode::VaultBalanceMismatch
+        );
+        require!(
+            ctx.accounts.provider_lp_account.amount >= lp_amount,
+            ErrorCode::InsufficientTokens
+        );
+
+        let outcome = pool.state().withdraw(lp_amount)?;
+        require!(
+            outcome.sol_amount >= min_sol_out && outcome.token_amount >= min_tokens_out,
+            ErrorCode::SlippageExceeded
+        );
+
+        let mint_key = ctx.accounts.mint.key();
+        let pool_seeds: &[&[&[u8]]] = &[&[b"pool", mint_key.as_ref(), &[pool.bump]]];
+        let vault_seeds: &[&[&[u8]]] = &[&[b"pool_sol_vault", mint_key.as_ref(), &[ctx.bumps.pool_sol_vault]]];
+
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    from: ctx.accounts.provider_lp_account.to_account_info(),
+                    authority: ctx.accounts.provider.to_account_info(),
+                },
+            ),
+            lp_amount,
+        )?;
+
+        // Transfer SOL: pool → provider
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.pool_sol_vault.to_account_info(),
+                    to: ctx.accounts.provider.to_account_info(),
+                },
+                vault_seeds,
+            ),
+            outcome.sol_amount,
+        )?;
+
+        // Transfer tokens: pool → provider
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.pool_token_account.to_account_info(),
+                    to: ctx.accounts.provider_token_account.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                pool_seeds,
+            ),
+            outcome.token_amount,
+        )?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.set_state(&outcome.state);
+
+        emit!(LiquidityRemoved {
+            mint: mint_key,
+            provider: ctx.accounts.provider.key(),
+            lp_amount,
+            sol_amount: outcome.sol_amount,
+            token_amount: outcome.token_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+/// Moves a buy priced by curve::execute_buy*: fee shares to their vaults, SOL
+/// into the curve, tokens to the buyer, then writes back the reserves and
+/// emits the trade.
+fn settle_buy(accounts: &mut Buy<'_>, bumps: &BuyBumps, outcome: &TradeOutcome) -> Result<()> {
+    let clock = Clock::get()?;
+    let sol_amount = outcome.sol_amount;
+    let fee_lamports = outcome.fee;
+    let sol_for_curve = outcome.sol_net;
+    let tokens_out = outcome.token_amount;
+
+    let referrer = accounts.referrer.as_ref().map(|r| r.key());
+    if let Some(referrer) = referrer {
+        require!(referrer != accounts.buyer.key(), ErrorCode::SelfReferral);
+        require!(
+            accounts.referral_stats.is_some() && accounts.referral_vault.is_some(),
+            ErrorCode::MissingReferralStats
+        );
+    }
+    let referral_share_bps = if referrer.is_some() {
+        accounts.bonding_curve.referral_fee_share_bps
+    } else {
+        0
+    };
+    let (platform_fee, creator_fee, referral_fee) = split_fee_with_referral(
+        fee_lamports,
+        accounts.bonding_curve.creator_fee_share_bps,
+        referral_share_bps,
+    )
+    .ok_or(ErrorCode::InvalidFeeBps)?;
+
+    require!(
+        accounts.bonding_curve_token_account.amount >= tokens_out,
+        ErrorCode::InsufficientTokens
+    );
+
+    // Transfer SOL: buyer → fee vault
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: accounts.buyer.to_account_info(),
+                to: accounts.fee_vault.to_account_info(),
+            },
+        ),
+        platform_fee,
+    )?;
+
+    // Transfer SOL: buyer → creator vault
+    if creator_fee > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: accounts.buyer.to_account_info(),
+                    to: accounts.creator_vault.to_account_info(),
+                },
+            ),
+            creator_fee,
+        )?;
+    }
+
+    // Transfer SOL: buyer → referral vault. A new vault is first topped up to
+    // its rent floor, like the creator vault at launch, so any reward can land
+    if let Some(vault) = accounts.referral_vault.as_ref() {
+        if referral_fee > 0 {
+            let top_up = Rent::get()?.minimum_balance(0).saturating_sub(vault.lamports());
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: accounts.buyer.to_account_info(),
+                        to: vault.to_account_info(),
+                    },
+                ),
+                math::add(referral_fee, top_up, "referral_fee + vault top_up")?,
+            )?;
+        }
+    }
+
+    // Transfer SOL: buyer → SOL vault
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: accounts.buyer.to_account_info(),
+                to: accounts.sol_vault.to_account_info(),
+            },
+        ),
+        sol_for_curve,
+    )?;
+
+    // Transfer tokens: bonding curve → buyer
+    let curve_bump = accounts.bonding_curve.bump;
+    let mint_key = accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"bonding_curve",
+        mint_key.as_ref(),
+        &[curve_bump],
+    ]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            Transfer {
+                from: accounts.bonding_curve_token_account.to_account_info(),
+                to: accounts.buyer_token_account.to_account_info(),
+                authority: accounts.bonding_curve.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        tokens_out,
+    )?;
+
+    let curve = &mut accounts.bonding_curve;
+    curve.update_price_accumulator(clock.unix_timestamp);
+    curve.set_state(&outcome.state);
+
+    let config = &mut accounts.platform_config;
+    config.total_fees_collected =
+        math::add(config.total_fees_collected, platform_fee, "total_fees_collected")?;
+
+    if let (Some(referrer), Some(stats)) = (referrer, accounts.referral_stats.as_mut()) {
+        let bump = bumps.referral_stats.ok_or(ErrorCode::MissingReferralStats)?;
+        stats.record(referrer, sol_amount, referral_fee, bump)?;
+    }
+
+    if curve.is_complete() {
+        curve.is_graduated = true;
+        emit!(TokenGraduated {
+            mint: accounts.mint.key(),
+            sol_raised: curve.real_sol_balance,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    emit!(TradeExecuted {
+        mint: accounts.mint.key(),
+        trader: accounts.buyer.key(),
+        is_buy: true,
+        sol_amount,
+        token_amount: tokens_out,
+        fee: fee_lamports,
+        platform_fee,
+        creator_fee,
+        referrer,
+        referral_fee,
+        timestamp: clock.unix_timestamp,
+        virtual_sol_reserve: curve.virtual_sol_reserve,
+        virtual_token_reserve: curve.virtual_token_reserve,
+    });
+
+    Ok(())
+}
+
+/// Moves a sell priced by curve::execute_sell*: tokens into the curve, SOL out
+/// to the seller and the fee shares, then writes back the reserves and emits
+/// the trade.
+fn settle_sell(accounts: &mut Sell<'_>, bumps: &SellBumps, outcome: &TradeOutcome) -> Result<()> {
+    let token_amount = outcome.token_amount;
+    let sol_out = outcome.sol_amount;
+    let fee_lamports = outcome.fee;
+    let sol_to_seller = outcome.sol_net;
+
+    require!(
+        accounts.seller_token_account.amount >= token_amount,
+        ErrorCode::InsufficientTokens
+    );
+
+    let referrer = accounts.referrer.as_ref().map(|r| r.key());
+    if let Some(referrer) = referrer {
+        require!(referrer != accounts.seller.key(), ErrorCode::SelfReferral);
+        require!(
+            accounts.referral_stats.is_some() && accounts.referral_vault.is_some(),
+            ErrorCode::MissingReferralStats
+        );
+    }
+    let referral_share_bps = if referrer.is_some() {
+        accounts.bonding_curve.referral_fee_share_bps
+    } else {
+        0
+    };
+    let (platform_fee, creator_fee, referral_fee) = split_fee_with_referral(
+        fee_lamports,
+        accounts.bonding_curve.creator_fee_share_bps,
+        referral_share_bps,
+    )
+    .ok_or(ErrorCode::InvalidFeeBps)?;
+
+    // The vault must actually hold what the curve thinks it raised
+    let rent_floor = Rent::get()?.minimum_balance(0);
+    require!(
+        vault_covers_balance(
+            accounts.bonding_curve.real_sol_balance,
+            accounts.sol_vault.lamports(),
+            rent_floor,
+        ),
+        ErrorCode::VaultBalanceMismatch
+    );
+
+    // Transfer tokens: seller → bonding curve
+    token::transfer(
+        CpiContext::new(
+            accounts.token_program.to_account_info(),
+            Transfer {
+                from: accounts.seller_token_account.to_account_info(),
+                to: accounts.bonding_curve_token_account.to_account_info(),
+                authority: accounts.seller.to_account_info(),
+            },
+        ),
+        token_amount,
+    )?;
+
+    let mint_key = accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"sol_vault",
+        mint_key.as_ref(),
+        &[bumps.sol_vault],
+    ]];
+
+    // Transfer SOL: SOL vault → seller
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: accounts.sol_vault.to_account_info(),
+                to: accounts.seller.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        sol_to_seller,
+    )?;
+
+    // Transfer fee: SOL vault → fee vault
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: accounts.sol_vault.to_account_info(),
+                to: accounts.fee_vault.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        platform_fee,
+    )?;
+
+    // Transfer creator share: SOL vault → creator vault
+    if creator_fee > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: accounts.sol_vault.to_account_info(),
+                    to: accounts.creator_vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            creator_fee,
+        )?;
+    }
+
+    // Transfer referral reward: SOL vault → referral vault, with the seller
+    // topping a new vault up to its rent floor first
+    if let Some(vault) = accounts.referral_vault.as_ref() {
+        if referral_fee > 0 {
+            let top_up = Rent::get()?.minimum_balance(0).saturating_sub(vault.lamports());
+            if top_up > 0 {
+                anchor_lang::system_program::transfer(
+                    CpiContext::new(
+                        accounts.system_program.to_account_info(),
+                        anchor_lang::system_program::Transfer {
+                            from: accounts.seller.to_account_info(),
+                            to: vault.to_account_info(),
+                        },
+                    ),
+                    top_up,
+                )?;
+            }
+            anchor_lang::system_program::transfer(
+                CpiContext::new_with_signer(
+                    accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: accounts.sol_vault.to_account_info(),
+                        to: vault.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                referral_fee,
+            )?;
+        }
+    }
+
+    let curve = &mut accounts.bonding_curve;
+    curve.update_price_accumulator(Clock::get()?.unix_timestamp);
+    curve.set_state(&outcome.state);
+
+    let config = &mut accounts.platform_config;
+    config.total_fees_collected =
+        math::add(config.total_fees_collected, platform_fee, "total_fees_collected")?;
+
+    if let (Some(referrer), Some(stats)) = (referrer, accounts.referral_stats.as_mut()) {
+        let bump = bumps.referral_stats.ok_or(ErrorCode::MissingReferralStats)?;
+        stats.record(referrer, sol_out, referral_fee, bump)?;
+    }
+
+    emit!(TradeExecuted {
+        mint: accounts.mint.key(),
+        trader: accounts.seller.key(),
+        is_buy: false,
+        sol_amount: sol_out,
+        token_amount,
+        fee: fee_lamports,
+        platform_fee,
+        creator_fee,
+        referrer,
+        referral_fee,
+        timestamp: Clock::get()?.unix_timestamp,
+        virtual_sol_reserve: curve.virtual_sol_reserve,
+        virtual_token_reserve: curve.virtual_token_reserve,
+    });
+
+    Ok(())
+}
+
+#[account]
+pub struct PlatformConfig {
+    pub admin: Pubkey,
+    pub fee_wallet: Pubkey,
+    pub fee_bps: u64,
+    pub grad_threshold: u64,
+    pub total_tokens: u64,
+    pub pending_admin: Option<Pubkey>,
+    pub paused: bool,
+    pub creator_fee_share_bps: u64,
+    pub referral_fee_share_bps: u64,
+    pub total_fees_collected: u64,
+    pub launch_fee_bps: u64,
+    pub launch_fee_duration: u64,
+    pub launch_fee_unit: LaunchFeeUnit,
+    pub creation_fee_lamports: u64,
+    pub launch_bounds: LaunchBounds,
+    pub bump: u8,
+}
+
+impl PlatformConfig {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + (1 + 32) + 1 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + LaunchBounds::LEN + 1;
+}
+
+/// Optional launch reserves a creator can pick in create_token — None keeps the default
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct LaunchParams {
+    pub virtual_sol_reserve: Option<u64>,
+    pub virtual_token_reserve: Option<u64>,
+    /// Tokens minted to the curve and sold through it
+    pub real_token_reserve: Option<u64>,
+}
+
+/// Everything a new curve is created with: the creator's reserves (or the
+/// defaults) and the fee terms snapshotted from the config, so later config
+/// changes never touch the token
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LaunchTerms {
     pub virtual_sol_reserve: u64,
     pub virtual_token_reserve: u64,
-    pub real_sol_balance: u64,
     pub real_token_reserve: u64,
-    pub token_total_supply: u64,
-    pub is_graduated: bool,
-    pub bump: u8,
+    pub curve_kind: CurveKind,
+    pub fee_bps: u64,
+    pub grad_threshold: u64,
+    pub creator_fee_share_bps: u64,
+    pub referral_fee_share_bps: u64,
+    pub launch_fee_bps: u64,
+    pub launch_fee_duration: u64,
+    pub launch_fee_unit: LaunchFeeUnit,
+}
+
+impl LaunchTerms {
+    pub fn new(config: &PlatformConfig, curve_kind: CurveKind, params: &LaunchParams) -> Self {
+        LaunchTerms {
+            virtual_sol_reserve: params.virtual_sol_reserve.unwrap_or(INITIAL_VIRTUAL_SOL_RESERVE),
+            virtual_token_reserve: params.virtual_token_reserve.unwrap_or(INITIAL_VIRTUAL_TOKEN_RESERVE),
+            real_token_reserve: params.real_token_reserve.unwrap_or(INITIAL_REAL_TOKEN_RESERVE),
+            curve_kind,
+            fee_bps: config.fee_bps,
+            grad_threshold: config.grad_threshold,
+            creator_fee_share_bps: config.creator_fee_share_bps,
+            referral_fee_share_bps: config.referral_fee_share_bps,
+            launch_fee_bps: config.launch_fee_bps,
+            launch_fee_duration: config.launch_fee_duration,
+            launch_fee_unit: config.launch_fee_unit,
+        }
+    }
+}
+
+impl Default for LaunchTerms {
+    /// The default constant-product launch under the default fees, no launch fee
+    fn default() -> Self {
+        LaunchTerms {
+            virtual_sol_reserve: INITIAL_VIRTUAL_SOL_RESERVE,
+            virtual_token_reserve: INITIAL_VIRTUAL_TOKEN_RESERVE,
+            real_token_reserve: INITIAL_REAL_TOKEN_RESERVE,
+            curve_kind: CurveKind::ConstantProduct,
+            fee_bps: PLATFORM_FEE_BPS,
+            grad_threshold: GRADUATION_THRESHOLD,
+            creator_fee_share_bps: 0,
+            referral_fee_share_bps: 0,
+            launch_fee_bps: 0,
+            launch_fee_duration: 0,
+            launch_fee_unit: LaunchFeeUnit::Seconds,
+        }
+    }
+}
+
+/// Admin-set range a creator's launch reserves must fall inside (inclusive)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LaunchBounds {
+    pub min_virtual_sol_reserve: u64,
+    pub max_virtual_sol_reserve: u64,
+    pub min_virtual_token_reserve: u64,
+    pub max_virtual_token_reserve: u64,
+    pub min_real_token_reserve: u64,
+    pub max_real_token_reserve: u64,
+}
+
+impl LaunchBounds {
+    pub const LEN: usize = 8 * 6;
+
+    /// Only the hard-coded default launch is allowed
+    pub fn defaults_only() -> Self {
+        LaunchBounds {
+            min_virtual_sol_reserve: INITIAL_VIRTUAL_SOL_RESERVE,
+            max_virtual_sol_reserve: INITIAL_VIRTUAL_SOL_RESERVE,
+            min_virtual_token_reserve: INITIAL_VIRTUAL_TOKEN_RESERVE,
+            max_virtual_token_reserve: INITIAL_VIRTUAL_TOKEN_RESERVE,
+            min_real_token_reserve: INITIAL_REAL_TOKEN_RESERVE,
+            max_real_token_reserve: INITIAL_REAL_TOKEN_RESERVE,
+        }
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.min_virtual_sol_reserve > 0
+            && self.min_virtual_sol_reserve <= self.max_virtual_sol_reserve
+            && self.min_virtual_token_reserve > 0
+            && self.min_virtual_token_reserve <= self.max_virtual_token_reserve
+            && self.min_real_token_reserve > 0
+            && self.min_real_token_reserve <= self.max_real_token_reserve
+    }
+
+    pub fn allows(&self, virtual_sol: u64, virtual_token: u64, real_token: u64) -> bool {
+        (self.min_virtual_sol_reserve..=self.max_virtual_sol_reserve).contains(&virtual_sol)
+            && (self.min_virtual_token_reserve..=self.max_virtual_token_reserve).contains(&virtual_token)
+            && (self.min_real_token_reserve..=self.max_real_token_reserve).contains(&real_token)
+            // The curve can never sell more than its virtual token reserve,
+            // nor more than the supply the token will ever have
+            && real_token < virtual_token
+            && real_token <= TOKEN_TOTAL_SUPPLY
+    }
+}
+
+#[account]
+pub struct TokenMeta {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub description: String,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl TokenMeta {
+    pub const LEN: usize = 8 + 32 + 32 + (4 + MAX_NAME_LEN) + (4 + MAX_SYMBOL_LEN) + (4 + MAX_URI_LEN) + (4 + MAX_URI_LEN) + 8 + 1;
+}
+
+#[account]
+pub struct BondingCurve {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub virtual_sol_reserve: u64,
+    pub virtual_token_reserve: u64,
+    pub real_sol_balance: u64,
+    pub real_token_reserve: u64,
+    pub token_total_supply: u64,
+    pub is_graduated: bool,
+    pub is_paused: bool,
+    /// Set once migrate_to_pool has moved the SOL and tokens into the Pool
+    pub is_migrated: bool,
+    pub fee_bps: u64,
+    pub grad_threshold: u64,
+    pub creator_fee_share_bps: u64,
+    /// Snapshotted with the creator share, so the two always fit in one fee
+    pub referral_fee_share_bps: u64,
+    pub initial_virtual_sol_reserve: u64,
+    pub initial_virtual_token_reserve: u64,
+    pub launch_fee_bps: u64,
+    pub launch_fee_duration: u64,
+    pub launch_fee_unit: LaunchFeeUnit,
+    pub created_at: i64,
+    pub created_slot: u64,
+    pub curve_kind: CurveKind,
+    /// Σ spot price × seconds, Q64.64 lamports per base unit. Wraps on overflow —
+    /// only the difference between two observations means anything.
+    pub price_cumulative: u128,
+    pub last_update_ts: i64,
+    pub bump: u8,
+}
+
+impl BondingCurve {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + CurveKind::LEN + 16 + 8 + 1;
+
+    /// A curve as create_token leaves it: nothing sold, nothing raised
+    pub fn launch(mint: Pubkey, creator: Pubkey, terms: &LaunchTerms, clock: &Clock, bump: u8) -> Self {
+        BondingCurve {
+            mint,
+            creator,
+            virtual_sol_reserve: terms.virtual_sol_reserve,
+            virtual_token_reserve: terms.virtual_token_reserve,
+            real_sol_balance: 0,
+            real_token_reserve: terms.real_token_reserve,
+            token_total_supply: 0,
+            is_graduated: false,
+            is_paused: false,
+            is_migrated: false,
+            fee_bps: terms.fee_bps,
+            grad_threshold: terms.grad_threshold,
+            creator_fee_share_bps: terms.creator_fee_share_bps,
+            referral_fee_share_bps: terms.referral_fee_share_bps,
+            initial_virtual_sol_reserve: terms.virtual_sol_reserve,
+            initial_virtual_token_reserve: terms.virtual_token_reserve,
+            launch_fee_bps: terms.launch_fee_bps,
+            launch_fee_duration: terms.launch_fee_duration,
+            launch_fee_unit: terms.launch_fee_unit,
+            created_at: clock.unix_timestamp,
+            created_slot: clock.slot,
+            curve_kind: terms.curve_kind,
+            price_cumulative: 0,
+            last_update_ts: clock.unix_timestamp,
+            bump,
+        }
+    }
+
+    /// Reserves in the shape curve.rs prices against
+    pub fn state(&self) -> CurveState {
+        CurveState {
+            virtual_sol_reserve: self.virtual_sol_reserve,
+            virtual_token_reserve: self.virtual_token_reserve,
+            real_sol_balance: self.real_sol_balance,
+            real_token_reserve: self.real_token_reserve,
+            tokens_sold: self.token_total_supply,
+        }
+    }
+
+    /// Writes back reserves priced by curve.rs
+    pub fn set_state(&mut self, state: &CurveState) {
+        self.virtual_sol_reserve = state.virtual_sol_reserve;
+        self.virtual_token_reserve = state.virtual_token_reserve;
+        self.real_sol_balance = state.real_sol_balance;
+        self.real_token_reserve = state.real_token_reserve;
+        self.token_total_supply = state.tokens_sold;
+    }
+
+    /// Raised its SOL target or sold every token it had for sale
+    pub fn is_complete(&self) -> bool {
+        self.real_sol_balance >= self.grad_threshold || self.real_token_reserve == 0
+    }
+
+    /// Fee a buy pays right now — the decaying launch fee, then the base fee
+    pub fn current_fee_bps(&self, clock: &Clock) -> u64 {
+        let elapsed = match self.launch_fee_unit {
+            LaunchFeeUnit::Seconds => clock.unix_timestamp.saturating_sub(self.created_at).max(0) as u64,
+            LaunchFeeUnit::Slots => clock.slot.saturating_sub(self.created_slot),
+        };
+        launch_fee_bps(self.fee_bps, self.launch_fee_bps, elapsed, self.launch_fee_duration)
+    }
+
+    /// Price accumulator as of `now`, counting the current spot price since the
+    /// last trade. Two of these, taken apart in time, give a TWAP (curve::twap).
+    /// Never fails: a price the shape can't express accrues as MAX_SPOT_PRICE_Q64.
+    pub fn observe(&self, now: i64) -> PriceObservation {
+        let price = self.curve_kind.spot_price_q64(&self.state()).unwrap_or(MAX_SPOT_PRICE_Q64);
+        let last = PriceObservation { price_cumulative: self.price_cumulative, timestamp: self.last_update_ts };
+        last.advance(price, now)
+    }
+
+    /// Accrue the pre-trade price up to `now` — call before the trade's set_state
+    pub fn update_price_accumulator(&mut self, now: i64) {
+        let observation = self.observe(now);
+        self.price_cumulative = observation.price_cumulative;
+        self.last_update_ts = observation.timestamp;
+    }
+}
+
+/// Post-graduation constant-product pool, priced by amm.rs
+#[account]
+pub struct Pool {
+    pub mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub sol_reserve: u64,
+    pub token_reserve: u64,
+    /// Every LP share, including locked_lp
+    pub lp_supply: u64,
+    /// Opening liquidity from the migration, held by the lp_locker PDA for good
+    pub locked_lp: u64,
+    pub fee_bps: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl Pool {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    /// Reserves in the shape amm.rs prices against
+    pub fn state(&self) -> PoolState {
+        PoolState {
+            sol_reserve: self.sol_reserve,
+            token_reserve: self.token_reserve,
+            lp_supply: self.lp_supply,
+        }
+    }
+
+    /// Writes back reserves priced by amm.rs
+    pub fn set_state(&mut self, state: &PoolState) {
+        self.sol_reserve = state.sol_reserve;
+        self.token_reserve = state.token_reserve;
+        self.lp_supply = state.lp_supply;
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SwapDirection {
+    SolToToken,
+    TokenToSol,
+}
+
+/// Price curve shape, chosen at creation. Prices are in curve::PRICE_SCALE units.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CurveKind {
+    ConstantProduct,
+    Linear { start_price: u64, end_price: u64 },
+    Exponential { start_price: u64, doubling_tokens: u64 },
+    Stepped { start_price: u64, step_tokens: u64, step_increment: u64 },
+}
+
+impl CurveKind {
+    /// Tag + the largest variant (Stepped)
+    pub const LEN: usize = 1 + 8 + 8 + 8;
+}
+
+impl PricingCurve for CurveKind {
+    fn tokens_out(&self, state: &CurveState, sol_in: u64) -> Option<u64> {
+        match *self {
+            CurveKind::ConstantProduct => ConstantProduct.tokens_out(state, sol_in),
+            CurveKind::Linear { start_price, end_price } => {
+                Linear { start_price, end_price }.tokens_out(state, sol_in)
+            }
+            CurveKind::Exponential { start_price, doubling_tokens } => {
+                Exponential { start_price, doubling_tokens }.tokens_out(state, sol_in)
+            }
+            CurveKind::Stepped { start_price, step_tokens, step_increment } => {
+                Stepped { start_price, step_tokens, step_increment }.tokens_out(state, sol_in)
+            }
+        }
+    }
+
+    fn sol_out(&self, state: &CurveState, tokens_in: u64) -> Option<u64> {
+        match *self {
+            CurveKind::ConstantProduct => ConstantProduct.sol_out(state, tokens_in),
+            CurveKind::Linear { start_price, end_price } => {
+                Linear { start_price, end_price }.sol_out(state, tokens_in)
+            }
+            CurveKind::Exponential { start_price, doubling_tokens } => {
+                Exponential { start_price, doubling_tokens }.sol_out(state, tokens_in)
+            }
+            CurveKind::Stepped { start_price, step_tokens, step_increment } => {
+                Stepped { start_price, step_tokens, step_increment }.sol_out(state, tokens_in)
+            }
+        }
+    }
+
+    fn sol_in(&self, state: &CurveState, tokens_out: u64) -> Option<u64> {
+        match *self {
+            CurveKind::ConstantProduct => ConstantProduct.sol_in(state, tokens_out),
+            CurveKind::Linear { start_price, end_price } => {
+                Linear { start_price, end_price }.sol_in(state, tokens_out)
+            }
+            CurveKind::Exponential { start_price, doubling_tokens } => {
+                Exponential { start_price, doubling_tokens }.sol_in(state, tokens_out)
+            }
+            CurveKind::Stepped { start_price, step_tokens, step_increment } => {
+                Stepped { start_price, step_tokens, step_increment }.sol_in(state, tokens_out)
+            }
+        }
+    }
+
+    fn tokens_in(&self, state: &CurveState, sol_out: u64) -> Option<u64> {
+        match *self {
+            CurveKind::ConstantProduct => ConstantProduct.tokens_in(state, sol_out),
+            CurveKind::Linear { start_price, end_price } => {
+                Linear { start_price, end_price }.tokens_in(state, sol_out)
+            }
+            CurveKind::Exponential { start_price, doubling_tokens } => {
+                Exponential { start_price, doubling_tokens }.tokens_in(state, sol_out)
+            }
+            CurveKind::Stepped { start_price, step_tokens, step_increment } => {
+                Stepped { start_price, step_tokens, step_increment }.tokens_in(state, sol_out)
+            }
+        }
+    }
+
+    fn spot_price(&self, state: &CurveState) -> Option<u128> {
+        match *self {
+            CurveKind::ConstantProduct => ConstantProduct.spot_price(state),
+            CurveKind::Linear { start_price, end_price } => {
+                Linear { start_price, end_price }.spot_price(state)
+            }
+            CurveKind::Exponential { start_price, doubling_tokens } => {
+                Exponential { start_price, doubling_tokens }.spot_price(state)
+            }
+            CurveKind::Stepped { start_price, step_tokens, step_increment } => {
+                Stepped { start_price, step_tokens, step_increment }.spot_price(state)
+            }
+        }
+    }
+
+    fn spot_price_q64(&self, state: &CurveState) -> Option<u128> {
+        match *self {
+            CurveKind::ConstantProduct => ConstantProduct.spot_price_q64(state),
+            CurveKind::Linear { start_price, end_price } => {
+                Linear { start_price, end_price }.spot_price_q64(state)
+            }
+            CurveKind::Exponential { start_price, doubling_tokens } => {
+                Exponential { start_price, doubling_tokens }.spot_price_q64(state)
+            }
+            CurveKind::Stepped { start_price, step_tokens, step_increment } => {
+                Stepped { start_price, step_tokens, step_increment }.spot_price_q64(state)
+            }
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        match *self {
+            CurveKind::ConstantProduct => ConstantProduct.is_valid(),
+            CurveKind::Linear { start_price, end_price } => {
+                Linear { start_price, end_price }.is_valid()
+            }
+            CurveKind::Exponential { start_price, doubling_tokens } => {
+                Exponential { start_price, doubling_tokens }.is_valid()
+            }
+            CurveKind::Stepped { start_price, step_tokens, step_increment } => {
+                Stepped { start_price, step_tokens, step_increment }.is_valid()
+            }
+        }
+    }
+}
+
+/// Clock the anti-sniping launch fee decays against
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LaunchFeeUnit {
+    Seconds,
+    Slots,
+}
+
+#[account]
+pub struct ReferralStats {
+    pub referrer: Pubkey,
+    pub total_volume: u64,
+    pub total_rewards: u64,
+    pub bump: u8,
+}
+
+impl ReferralStats {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 1;
+
+    /// Adds one referred trade's volume and reward
+    pub fn record(&mut self, referrer: Pubkey, volume: u64, reward: u64, bump: u8) -> Result<()> {
+        self.referrer = referrer;
+        self.total_volume = math::add(self.total_volume, volume, "referral total_volume")?;
+        self.total_rewards = math::add(self.total_rewards, reward, "referral total_rewards")?;
+        self.bump = bump;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializePlatform<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = admin,
+        space = PlatformConfig::LEN,
+        seeds = [b"platform_config"],
+        bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    
+    /// CHECK: just storing the pubkey as the fee destination
+    pub fee_wallet: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"fee_vault"],
+        bump,
+    )]
+    pub fee_vault: SystemAccount<'info>,
+    
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Smooth>,
+    
+    // Only the upgrade authority may initialize — stops anyone front-running a fresh deploy
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePlatformConfig<'info> {
+    pub admin: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    pub admin: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub new_admin: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = platform_config.pending_admin == Some(new_admin.key()) @ ErrorCode::NotPendingAdmin,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAdminTransfer<'info> {
+    pub admin: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetLaunchBounds<'info> {
+    pub admin: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetLaunchFeeSchedule<'info> {
+    pub admin: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetPlatformPaused<'info> {
+    pub admin: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetTokenPaused<'info> {
+    pub admin: Signer<'info>,
+    
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", mint.key().as_ref()],
+        bump = bonding_curve.bump,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+}
+
+#[derive(Accounts)]
+pub struct CreateToken<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    
+    #[account(
+        init,
+        payer = creator,
+        mint::decimals = TOKEN_DECIMALS,
+        mint::authority = bonding_curve,
+        mint::freeze_authority = bonding_curve,
+    )]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = TokenMeta::LEN,
+        seeds = [b"token_meta", mint.key().as_ref()],
+        bump
+    )]
+    pub token_meta: Account<'info, TokenMeta>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = BondingCurve::LEN,
+        seeds = [b"bonding_curve", mint.key().as_ref()],
+        bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = mint,
+        associated_token::authority = bonding_curve,
+    )]
+    pub bonding_curve_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"creator_vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub creator_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"sol_vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"fee_vault"],
+        bump,
+    )]
+    pub fee_vault: SystemAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct Buy<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", mint.key().as_ref()],
+        bump = bonding_curve.bump,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = bonding_curve,
+    )]
+    pub bonding_curve_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = mint,
+        associated_token::authority = buyer,
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"fee_vault"],
+        bump,
+    )]
+    pub fee_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"creator_vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub creator_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"sol_vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: SystemAccount<'info>,
+    
+    pub referrer: Option<SystemAccount<'info>>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = ReferralStats::LEN,
+        seeds = [b"referral", referrer.as_ref().map(|r| r.key()).unwrap_or_default().as_ref()],
+        bump,
+    )]
+    pub referral_stats: Option<Account<'info, ReferralStats>>,
+    
+    #[account(
+        mut,
+        seeds = [b"referral_vault", referrer.as_ref().map(|r| r.key()).unwrap_or_default().as_ref()],
+        bump,
+    )]
+    pub referral_vault: Option<SystemAccount<'info>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Sell<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", mint.key().as_ref()],
+        bump = bonding_curve.bump,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = bonding_curve,
+    )]
+    pub bonding_curve_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = seller,
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"fee_vault"],
+        bump,
+    )]
+    pub fee_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"creator_vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub creator_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"sol_vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: SystemAccount<'info>,
+    
+    pub referrer: Option<SystemAccount<'info>>,
+    
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = ReferralStats::LEN,
+        seeds = [b"referral", referrer.as_ref().map(|r| r.key()).unwrap_or_default().as_ref()],
+        bump,
+    )]
+    pub referral_stats: Option<Account<'info, ReferralStats>>,
+    
+    #[account(
+        mut,
+        seeds = [b"referral_vault", referrer.as_ref().map(|r| r.key()).unwrap_or_default().as_ref()],
+        bump,
+    )]
+    pub referral_vault: Option<SystemAccount<'info>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"bonding_curve", mint.key().as_ref()],
+        bump = bonding_curve.bump,
+        has_one = creator @ ErrorCode::Unauthorized,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    
+    #[account(
+        mut,
+        seeds = [b"creator_vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub creator_vault: SystemAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
-impl BondingCurve {
-    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 1;
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"referral_vault", referrer.key().as_ref()],
+        bump,
+    )]
+    pub referral_vault: SystemAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct InitializePlatform<'info> {
-    #[account(mut)]
+pub struct WithdrawFees<'info> {
     pub admin: Signer<'info>,
     
     #[account(
-        init,
-        payer = admin,
-        space = PlatformConfig::LEN,
         seeds = [b"platform_config"],
-        bump
+        bump = platform_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+        has_one = fee_wallet @ ErrorCode::InvalidFeeWallet,
     )]
     pub platform_config: Account<'info, PlatformConfig>,
     
-    /// CHECK: just storing the pubkey as the fee destination
+    #[account(
+        mut,
+        seeds = [b"fee_vault"],
+        bump,
+    )]
+    pub fee_vault: SystemAccount<'info>,
+    
+    /// CHECK: must be platform_config.fee_wallet — fees are only ever paid there
+    #[account(mut)]
     pub fee_wallet: UncheckedAccount<'info>,
     
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct CreateToken<'info> {
+pub struct Graduate<'info> {
     #[account(mut)]
-    pub creator: Signer<'info>,
+    pub caller: Signer<'info>,
     
     #[account(
         seeds = [b"platform_config"],
@@ -455,58 +2252,22 @@ pub struct CreateToken<'info> {
     )]
     pub platform_config: Account<'info, PlatformConfig>,
     
-    #[account(
-        init,
-        payer = creator,
-        mint::decimals = TOKEN_DECIMALS,
-        mint::authority = bonding_curve,
-        mint::freeze_authority = bonding_curve,
-    )]
     pub mint: Account<'info, Mint>,
     
     #[account(
-        init,
-        payer = creator,
-        space = TokenMeta::LEN,
-        seeds = [b"token_meta", mint.key().as_ref()],
-        bump
-    )]
-    pub token_meta: Account<'info, TokenMeta>,
-    
-    #[account(
-        init,
-        payer = creator,
-        space = BondingCurve::LEN,
+        mut,
         seeds = [b"bonding_curve", mint.key().as_ref()],
-        bump
+        bump = bonding_curve.bump,
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
-    
-    #[account(
-        init,
-        payer = creator,
-        associated_token::mint = mint,
-        associated_token::authority = bonding_curve,
-    )]
-    pub bonding_curve_token_account: Account<'info, TokenAccount>,
-    
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct Buy<'info> {
+pub struct MigrateToPool<'info> {
     #[account(mut)]
-    pub buyer: Signer<'info>,
-    
-    #[account(
-        seeds = [b"platform_config"],
-        bump = platform_config.bump,
-    )]
-    pub platform_config: Account<'info, PlatformConfig>,
+    pub payer: Signer<'info>,
     
+    #[account(mut)]
     pub mint: Account<'info, Mint>,
     
     #[account(
@@ -523,20 +2284,63 @@ pub struct Buy<'info> {
     )]
     pub bonding_curve_token_account: Account<'info, TokenAccount>,
     
+    #[account(
+        mut,
+        seeds = [b"sol_vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: SystemAccount<'info>,
+    
+    // init_if_needed so a second call reaches the AlreadyMigrated check, and a
+    // pool ATA someone created ahead of time can't block the migration
     #[account(
         init_if_needed,
-        payer = buyer,
+        payer = payer,
+        space = Pool::LEN,
+        seeds = [b"pool", mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"lp_mint", mint.key().as_ref()],
+        bump,
+        mint::decimals = TOKEN_DECIMALS,
+        mint::authority = pool,
+    )]
+    pub lp_mint: Account<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
         associated_token::mint = mint,
-        associated_token::authority = buyer,
+        associated_token::authority = pool,
     )]
-    pub buyer_token_account: Account<'info, TokenAccount>,
+    pub pool_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the locked opening LP — the program never signs for it
+    #[account(
+        seeds = [b"lp_locker", mint.key().as_ref()],
+        bump,
+    )]
+    pub lp_locker: UncheckedAccount<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = lp_mint,
+        associated_token::authority = lp_locker,
+    )]
+    pub locked_lp_account: Account<'info, TokenAccount>,
     
     #[account(
         mut,
-        constraint = fee_wallet.key() == platform_config.fee_wallet
+        seeds = [b"pool_sol_vault", mint.key().as_ref()],
+        bump,
     )]
-    /// CHECK: verified via constraint
-    pub fee_wallet: UncheckedAccount<'info>,
+    pub pool_sol_vault: SystemAccount<'info>,
     
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -544,9 +2348,9 @@ pub struct Buy<'info> {
 }
 
 #[derive(Accounts)]
-pub struct Sell<'info> {
+pub struct Swap<'info> {
     #[account(mut)]
-    pub seller: Signer<'info>,
+    pub trader: Signer<'info>,
     
     #[account(
         seeds = [b"platform_config"],
@@ -557,7 +2361,6 @@ pub struct Sell<'info> {
     pub mint: Account<'info, Mint>,
     
     #[account(
-        mut,
         seeds = [b"bonding_curve", mint.key().as_ref()],
         bump = bonding_curve.bump,
     )]
@@ -565,33 +2368,42 @@ pub struct Sell<'info> {
     
     #[account(
         mut,
-        associated_token::mint = mint,
-        associated_token::authority = bonding_curve,
+        seeds = [b"pool", mint.key().as_ref()],
+        bump = pool.bump,
     )]
-    pub bonding_curve_token_account: Account<'info, TokenAccount>,
+    pub pool: Account<'info, Pool>,
     
     #[account(
         mut,
         associated_token::mint = mint,
-        associated_token::authority = seller,
+        associated_token::authority = pool,
     )]
-    pub seller_token_account: Account<'info, TokenAccount>,
+    pub pool_token_account: Account<'info, TokenAccount>,
     
     #[account(
         mut,
-        constraint = fee_wallet.key() == platform_config.fee_wallet
+        seeds = [b"pool_sol_vault", mint.key().as_ref()],
+        bump,
     )]
-    /// CHECK: verified via constraint
-    pub fee_wallet: UncheckedAccount<'info>,
+    pub pool_sol_vault: SystemAccount<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = trader,
+        associated_token::mint = mint,
+        associated_token::authority = trader,
+    )]
+    pub trader_token_account: Account<'info, TokenAccount>,
     
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Graduate<'info> {
+pub struct Liquidity<'info> {
     #[account(mut)]
-    pub caller: Signer<'info>,
+    pub provider: Signer<'info>,
     
     #[account(
         seeds = [b"platform_config"],
@@ -602,11 +2414,55 @@ pub struct Graduate<'info> {
     pub mint: Account<'info, Mint>,
     
     #[account(
-        mut,
         seeds = [b"bonding_curve", mint.key().as_ref()],
         bump = bonding_curve.bump,
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", mint.key().as_ref()],
+        bump = pool.bump,
+        has_one = lp_mint,
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    #[account(mut)]
+    pub lp_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = pool,
+    )]
+    pub pool_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool_sol_vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub pool_sol_vault: SystemAccount<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = provider,
+        associated_token::mint = mint,
+        associated_token::authority = provider,
+    )]
+    pub provider_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = provider,
+        associated_token::mint = lp_mint,
+        associated_token::authority = provider,
+    )]
+    pub provider_lp_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 }
 
 #[event]
@@ -616,6 +2472,76 @@ pub struct PlatformInitialized {
     pub grad_threshold: u64,
 }
 
+#[event]
+pub struct PlatformConfigUpdated {
+    pub admin: Pubkey,
+    pub old_fee_bps: u64,
+    pub new_fee_bps: u64,
+    pub old_fee_wallet: Pubkey,
+    pub new_fee_wallet: Pubkey,
+    pub old_grad_threshold: u64,
+    pub new_grad_threshold: u64,
+    pub old_creator_fee_share_bps: u64,
+    pub new_creator_fee_share_bps: u64,
+    pub old_referral_fee_share_bps: u64,
+    pub new_referral_fee_share_bps: u64,
+    pub old_creation_fee_lamports: u64,
+    pub new_creation_fee_lamports: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminTransferProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminTransferAccepted {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminTransferCancelled {
+    pub admin: Pubkey,
+    pub cancelled_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LaunchBoundsUpdated {
+    pub admin: Pubkey,
+    pub bounds: LaunchBounds,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LaunchFeeScheduleUpdated {
+    pub admin: Pubkey,
+    pub launch_fee_bps: u64,
+    pub duration: u64,
+    pub unit: LaunchFeeUnit,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlatformPauseToggled {
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokenPauseToggled {
+    pub mint: Pubkey,
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct TokenCreated {
     pub mint: Pubkey,
@@ -623,6 +2549,10 @@ pub struct TokenCreated {
     pub name: String,
     pub symbol: String,
     pub uri: String,
+    pub creation_fee: u64,
+    pub virtual_sol_reserve: u64,
+    pub virtual_token_reserve: u64,
+    pub real_token_reserve: u64,
     pub timestamp: i64,
 }
 
@@ -634,11 +2564,38 @@ pub struct TradeExecuted {
     pub sol_amount: u64,
     pub token_amount: u64,
     pub fee: u64,
+    pub platform_fee: u64,
+    pub creator_fee: u64,
+    pub referrer: Option<Pubkey>,
+    pub referral_fee: u64,
     pub timestamp: i64,
     pub virtual_sol_reserve: u64,
     pub virtual_token_reserve: u64,
 }
 
+#[event]
+pub struct CreatorFeesClaimed {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralRewardsClaimed {
+    pub referrer: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub admin: Pubkey,
+    pub fee_wallet: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct TokenGraduated {
     pub mint: Pubkey,
@@ -646,6 +2603,48 @@ pub struct TokenGraduated {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct PoolCreated {
+    pub mint: Pubkey,
+    pub pool: Pubkey,
+    pub sol_reserve: u64,
+    pub token_reserve: u64,
+    pub locked_lp: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolSwapped {
+    pub mint: Pubkey,
+    pub trader: Pubkey,
+    pub is_buy: bool,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub sol_reserve: u64,
+    pub token_reserve: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityAdded {
+    pub mint: Pubkey,
+    pub provider: Pubkey,
+    pub lp_amount: u64,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityRemoved {
+    pub mint: Pubkey,
+    pub provider: Pubkey,
+    pub lp_amount: u64,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub timestamp: i64,
+}
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("Name is empty or too long (max 32 chars)")]
@@ -657,7 +2656,7 @@ pub enum ErrorCode {
     #[msg("URI is empty or too long (max 200 chars)")]
     InvalidUri,
     
-    #[msg("Token has already graduated — trading closed")]
+    #[msg("Token has already graduated — trade it in its pool")]
     TokenGraduated,
     
     #[msg("Amount must be greater than zero")]
@@ -671,10 +2670,80 @@ pub enum ErrorCode {
     
     #[msg("Bonding curve does not have enough SOL")]
     InsufficientSol,
+
+    #[msg("SOL vault balance does not match the curve's recorded balance")]
+    VaultBalanceMismatch,
     
     #[msg("Token has not raised enough SOL to graduate yet")]
     NotReadyToGraduate,
     
     #[msg("Token has already been graduated")]
     AlreadyGraduated,
-}
\ No newline at end of file
+    
+    #[msg("Token has not graduated yet — nothing to migrate")]
+    NotGraduated,
+    
+    #[msg("Signer is not the platform admin")]
+    Unauthorized,
+    
+    #[msg("Fee bps must not exceed 10000")]
+    InvalidFeeBps,
+    
+    #[msg("Graduation threshold must be greater than zero")]
+    InvalidGraduationThreshold,
+    
+    #[msg("Signer is not the pending admin")]
+    NotPendingAdmin,
+    
+    #[msg("No admin transfer is pending")]
+    NoPendingAdmin,
+    
+    #[msg("Trading and token creation are paused")]
+    Paused,
+    
+    #[msg("Trader cannot refer themselves")]
+    SelfReferral,
+    
+    #[msg("Referral stats and vault accounts are required when a referrer is given")]
+    MissingReferralStats,
+    
+    #[msg("Curve parameters are invalid for the chosen curve kind")]
+    InvalidCurveParams,
+    
+    #[msg("Launch reserves are outside the platform bounds")]
+    InvalidLaunchParams,
+    
+    #[msg("Arithmetic overflow or underflow — see program logs for the failing value")]
+    MathOverflow,
+    
+    #[msg("Fees can only be withdrawn to the platform fee wallet")]
+    InvalidFeeWallet,
+    
+    #[msg("Token has already been migrated to its pool")]
+    AlreadyMigrated,
+    
+    #[msg("Creation fee exceeds MAX_CREATION_FEE_LAMPORTS")]
+    InvalidCreationFee,
+}
+
+impl From<TradeError> for ErrorCode {
+    fn from(err: TradeError) -> Self {
+        match err {
+            TradeError::ZeroAmount => ErrorCode::ZeroAmount,
+            TradeError::MathOverflow(what) => {
+                msg!("MathOverflow: {}", what);
+                ErrorCode::MathOverflow
+            }
+            TradeError::InvalidFeeBps => ErrorCode::InvalidFeeBps,
+            TradeError::InsufficientTokens => ErrorCode::InsufficientTokens,
+            TradeError::InsufficientSol => ErrorCode::InsufficientSol,
+            TradeError::CurveComplete => ErrorCode::TokenGraduated,
+        }
+    }
+}
+
+impl From<TradeError> for anchor_lang::error::Error {
+    fn from(err: TradeError) -> Self {
+        ErrorCode::from(err).into()
+    }
+}
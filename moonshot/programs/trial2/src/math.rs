@@ -0,0 +1,204 @@
+// ─────────────────────────────────────────────────────────────────────────────
+// programs/trial/src/math.rs
+//
+// Checked arithmetic for on-chain state.
+// Every overflow / underflow becomes ErrorCode::MathOverflow instead of a
+// program panic, and the failing operation is logged so clients can tell
+// which number went wrong. Trade pricing reports the same through
+// curve::TradeError::MathOverflow, which names the value and is logged the
+// same way when lib.rs maps it onto ErrorCode.
+// ─────────────────────────────────────────────────────────────────────────────
+
+use anchor_lang::prelude::*;
+
+use crate::ErrorCode;
+
+// `what` names the value being computed, e.g. "real_sol_balance", and ends
+// up in the program log next to the operands.
+
+/// a + b
+pub fn add(a: u64, b: u64, what: &'static str) -> Result<u64> {
+    a.checked_add(b).ok_or_else(|| overflow(what, a, "+", b))
+}
+
+/// a − b
+pub fn sub(a: u64, b: u64, what: &'static str) -> Result<u64> {
+    a.checked_sub(b).ok_or_else(|| overflow(what, a, "-", b))
+}
+
+/// Unwrap a checked computation that has no single pair of operands to log
+pub fn checked<T>(value: Option<T>, what: &'static str) -> Result<T> {
+    value.ok_or_else(|| {
+        msg!("MathOverflow: {}", what);
+        error!(ErrorCode::MathOverflow)
+    })
+}
+
+fn overflow(what: &'static str, a: u64, op: &str, b: u64) -> Error {
+    msg!("MathOverflow: {} = {} {} {}", what, a, op, b);
+    error!(ErrorCode::MathOverflow)
+}
+
+// ─── Unit Tests ───────────────────────────────────────────────────────────────
+// Each test drives one overflow / underflow path on purpose and checks it
+// comes back as MathOverflow instead of a panic.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::*;
+    use crate::{BondingCurve, LaunchTerms, ReferralStats};
+
+    fn math_overflow() -> Error {
+        ErrorCode::MathOverflow.into()
+    }
+
+    // Helper: the error an instruction would return for a curve.rs result
+    fn on_chain<T>(result: std::result::Result<T, TradeError>) -> Result<T> {
+        result.map_err(Error::from)
+    }
+
+    // Helper: a freshly launched curve with the default reserves
+    fn curve() -> BondingCurve {
+        BondingCurve::launch(Pubkey::default(), Pubkey::default(), &LaunchTerms::default(), &Clock::default(), 255)
+    }
+
+    // ─── 1. Primitives ────────────────────────────────────────────────────
+
+    #[test]
+    fn test_add_and_sub_report_overflow() {
+        assert_eq!(add(2, 3, "x").unwrap(), 5);
+        assert_eq!(sub(5, 3, "x").unwrap(), 2);
+        assert_eq!(add(u64::MAX, 1, "x").unwrap_err(), math_overflow());
+        assert_eq!(sub(0, 1, "x").unwrap_err(), math_overflow());
+        assert_eq!(checked(Some(7), "x").unwrap(), 7);
+        assert_eq!(checked::<u64>(None, "x").unwrap_err(), math_overflow());
+    }
+
+    #[test]
+    fn test_fee_for_large_amounts_does_not_overflow() {
+        // u64::MAX × 10000 does not fit in u64, so a plain checked_mul would fail here
+        assert_eq!(fee_for(u64::MAX, BPS_DENOMINATOR), Ok(u64::MAX));
+        assert_eq!(fee_for(u64::MAX, 100), Ok(u64::MAX / 100));
+    }
+
+    #[test]
+    fn test_fee_above_100_percent_is_rejected() {
+        assert_eq!(on_chain(fee_for(1_000, BPS_DENOMINATOR + 1)).unwrap_err(), ErrorCode::InvalidFeeBps.into());
+
+        // A misconfigured curve fee surfaces as an error from buy, not a panic
+        let c = curve();
+        let outcome = execute_buy(&c.curve_kind, &c.state(), 1_000, BPS_DENOMINATOR + 1, u64::MAX);
+        assert_eq!(on_chain(outcome).unwrap_err(), ErrorCode::InvalidFeeBps.into());
+    }
+
+    // ─── 2. Curve reserves ────────────────────────────────────────────────
+
+    #[test]
+    fn test_after_buy_overflows_sol_reserves() {
+        let mut c = curve();
+        c.virtual_sol_reserve = u64::MAX;
+        assert_eq!(c.state().after_buy(1, 1), Err(TradeError::MathOverflow("virtual_sol_reserve")));
+        assert_eq!(on_chain(c.state().after_buy(1, 1)).unwrap_err(), math_overflow());
+
+        let mut c = curve();
+        c.real_sol_balance = u64::MAX;
+        assert_eq!(on_chain(c.state().after_buy(1, 1)).unwrap_err(), math_overflow());
+    }
+
+    #[test]
+    fn test_after_buy_underflows_token_reserves() {
+        // More tokens than the curve has left to sell
+        let c = curve();
+        let too_many = c.real_token_reserve + 1;
+        assert_eq!(on_chain(c.state().after_buy(1, too_many)).unwrap_err(), math_overflow());
+
+        let mut c = curve();
+        c.virtual_token_reserve = 0;
+        assert_eq!(on_chain(c.state().after_buy(1, 1)).unwrap_err(), math_overflow());
+    }
+
+    #[test]
+    fn test_after_buy_overflows_total_supply() {
+        let mut c = curve();
+        c.token_total_supply = u64::MAX;
+        assert_eq!(on_chain(c.state().after_buy(1, 1)).unwrap_err(), math_overflow());
+    }
+
+    #[test]
+    fn test_after_sell_underflows_sol_and_supply() {
+        // Paying out more SOL than was raised
+        let c = curve();
+        assert_eq!(on_chain(c.state().after_sell(0, 1)).unwrap_err(), math_overflow());
+
+        // Selling back tokens that were never sold
+        let mut c = curve();
+        c.real_sol_balance = 1_000;
+        assert_eq!(on_chain(c.state().after_sell(1, 1)).unwrap_err(), math_overflow());
+    }
+
+    #[test]
+    fn test_after_sell_overflows_token_reserves() {
+        let mut c = curve();
+        c.real_sol_balance = 1_000;
+        c.token_total_supply = u64::MAX;
+        c.virtual_token_reserve = u64::MAX;
+        assert_eq!(on_chain(c.state().after_sell(1, 1)).unwrap_err(), math_overflow());
+
+        let mut c = curve();
+        c.real_sol_balance = 1_000;
+        c.token_total_supply = u64::MAX;
+        c.real_token_reserve = u64::MAX;
+        assert_eq!(on_chain(c.state().after_sell(1, 1)).unwrap_err(), math_overflow());
+    }
+
+    #[test]
+    fn test_buy_then_sell_restores_reserves() {
+        let mut c = curve();
+        let bought = c.state().after_buy(1_000_000_000, 34_612_903_225_806).unwrap();
+        c.set_state(&bought.after_sell(34_612_903_225_806, 1_000_000_000).unwrap());
+        assert_eq!(c.virtual_sol_reserve, INITIAL_VIRTUAL_SOL_RESERVE);
+        assert_eq!(c.real_token_reserve, INITIAL_REAL_TOKEN_RESERVE);
+        assert_eq!(c.token_total_supply, 0);
+    }
+
+    // ─── 3. Counters ──────────────────────────────────────────────────────
+
+    #[test]
+    fn test_referral_stats_overflow() {
+        let mut stats = ReferralStats {
+            referrer: Pubkey::default(),
+            total_volume: u64::MAX,
+            total_rewards: 0,
+            bump: 0,
+        };
+        assert_eq!(stats.record(Pubkey::default(), 1, 0, 0).unwrap_err(), math_overflow());
+
+        stats.total_volume = 0;
+        stats.total_rewards = u64::MAX;
+        assert_eq!(stats.record(Pubkey::default(), 0, 1, 0).unwrap_err(), math_overflow());
+    }
+
+    #[test]
+    fn test_fee_counter_overflow() {
+        // total_fees_collected and total_tokens go through the same helper
+        let collected = u64::MAX - 5;
+        assert_eq!(add(collected, 6, "total_fees_collected").unwrap_err(), math_overflow());
+    }
+
+    // ─── 4. Price accumulator ─────────────────────────────────────────────
+
+    #[test]
+    fn test_unpriceable_curve_still_accrues() {
+        // No Q64.64 spot price — the accumulator saturates instead of failing the trade
+        let mut c = curve();
+        c.virtual_token_reserve = 0;
+        assert_eq!(c.curve_kind.spot_price_q64(&c.state()), None);
+
+        let start = c.last_update_ts;
+        c.update_price_accumulator(start + 3);
+        assert_eq!(c.last_update_ts, start + 3);
+        assert_eq!(c.price_cumulative, MAX_SPOT_PRICE_Q64.wrapping_mul(3));
+        assert_eq!(c.observe(start + 4).price_cumulative, MAX_SPOT_PRICE_Q64.wrapping_mul(4));
+    }
+}
@@ -0,0 +1,143 @@
+// ─────────────────────────────────────────────────────────────────────────────
+// programs/trial/tests/common/mod.rs
+//
+// Curve invariant model shared by the proptest suite (tests/curve_props.rs)
+// and the fuzz target (fuzz/fuzz_targets/curve_sequence.rs).
+// One trader runs a sequence of buys and sells through the pure curve.rs
+// functions; every step asserts the invariants, so a violation panics with
+// the reserves that broke it and the harness shrinks the sequence.
+// ─────────────────────────────────────────────────────────────────────────────
+
+#![allow(dead_code)]
+
+use trial2::{calculate_sol_out, calculate_tokens_out, deduct_fee};
+
+/// One step of a trade sequence
+#[derive(Clone, Copy, Debug)]
+pub enum Op {
+    /// Buy with this many lamports, fee included
+    Buy(u64),
+    /// Sell this share of the tokens held, in bps (clamped to 10000)
+    Sell(u16),
+}
+
+/// A constant-product curve plus the one trader trading against it
+#[derive(Debug)]
+pub struct CpModel {
+    pub vsr: u64,       // virtual sol reserve
+    pub vtr: u64,       // virtual token reserve
+    pub raised: u64,    // real SOL the curve holds
+    pub held: u64,      // tokens the trader holds (= tokens sold)
+    pub paid: u128,     // lamports the trader has paid in, fees included
+    pub received: u128, // lamports the trader has been paid, after fees
+}
+
+impl CpModel {
+    pub fn new(vsr: u64, vtr: u64) -> Self {
+        CpModel { vsr, vtr, raised: 0, held: 0, paid: 0, received: 0 }
+    }
+
+    fn k(&self) -> u128 {
+        self.vsr as u128 * self.vtr as u128
+    }
+
+    pub fn apply(&mut self, op: Op) {
+        match op {
+            Op::Buy(sol_amount) => self.buy(sol_amount),
+            Op::Sell(share_bps) => {
+                let share = share_bps.min(10_000) as u128;
+                self.sell((self.held as u128 * share / 10_000) as u64);
+            }
+        }
+    }
+
+    pub fn buy(&mut self, sol_amount: u64) {
+        let (sol_for_curve, fee) = deduct_fee(sol_amount);
+        assert_eq!(sol_for_curve + fee, sol_amount, "fee split lost lamports");
+        if sol_for_curve == 0 { return; }
+
+        let tokens = calculate_tokens_out(self.vsr, self.vtr, sol_for_curve)
+            .expect("tokens_out must price any non-zero buy");
+
+        // The u64 downcast inside calculate_tokens_out must be lossless
+        let exact = tokens_out_u128(self.vsr, self.vtr, sol_for_curve);
+        assert!(exact <= u64::MAX as u128, "tokens_out {} does not fit in u64", exact);
+        assert_eq!(tokens as u128, exact, "tokens_out was truncated");
+
+        // Reserves never underflow
+        assert!(tokens < self.vtr, "bought {} of a {} reserve", tokens, self.vtr);
+
+        // The program rejects trades that move nothing or overflow a reserve
+        if tokens == 0 { return; }
+        let (Some(vsr), Some(raised)) = (self.vsr.checked_add(sol_for_curve), self.raised.checked_add(sol_for_curve))
+        else { return; };
+
+        let k_before = self.k();
+        self.vsr = vsr;
+        self.vtr -= tokens;
+        self.raised = raised;
+        self.held += tokens;
+        self.paid += sol_amount as u128;
+        assert!(self.k() >= k_before, "k shrank on buy: {} → {} ({:?})", k_before, self.k(), self);
+
+        // Selling straight back never returns more than went in
+        let back = calculate_sol_out(self.vsr, self.vtr, tokens).unwrap_or(0);
+        assert!(back <= sol_for_curve, "buy→sell cycle profit: paid {} got {}", sol_for_curve, back);
+    }
+
+    pub fn sell(&mut self, tokens: u64) {
+        if tokens == 0 { return; }
+        assert!(tokens <= self.held);
+
+        let sol_out = calculate_sol_out(self.vsr, self.vtr, tokens).unwrap_or(0);
+
+        let exact = sol_out_u128(self.vsr, self.vtr, tokens);
+        assert!(exact <= u64::MAX as u128, "sol_out {} does not fit in u64", exact);
+        assert_eq!(sol_out as u128, exact, "sol_out was truncated");
+
+        // Tokens that were bought can always be paid for out of what was raised
+        assert!(sol_out <= self.raised, "sell pays {} but only {} raised ({:?})", sol_out, self.raised, self);
+        assert!(sol_out < self.vsr, "sell pays {} of a {} reserve", sol_out, self.vsr);
+
+        if sol_out == 0 { return; }
+        let vtr = self.vtr.checked_add(tokens).expect("token reserve cannot overflow: tokens came out of it");
+
+        let k_before = self.k();
+        self.vsr -= sol_out;
+        self.vtr = vtr;
+        self.raised -= sol_out;
+        self.held -= tokens;
+        assert!(self.k() >= k_before, "k shrank on sell: {} → {} ({:?})", k_before, self.k(), self);
+
+        let (sol_to_seller, fee) = deduct_fee(sol_out);
+        assert_eq!(sol_to_seller + fee, sol_out, "fee split lost lamports");
+        self.received += sol_to_seller as u128;
+    }
+
+    /// Sell everything still held, then check the trader came out behind
+    pub fn close(&mut self) {
+        self.sell(self.held);
+        assert!(self.received <= self.paid, "sequence profit: paid {} received {}", self.paid, self.received);
+    }
+}
+
+/// Run a whole sequence from the given virtual reserves
+pub fn run(vsr: u64, vtr: u64, ops: &[Op]) {
+    let mut model = CpModel::new(vsr, vtr);
+    for &op in ops {
+        model.apply(op);
+    }
+    model.close();
+}
+
+// Reference versions of the curve maths with no downcast, to compare against
+
+fn tokens_out_u128(vsr: u64, vtr: u64, sol_in: u64) -> u128 {
+    let k = vsr as u128 * vtr as u128;
+    vtr as u128 - k.div_ceil(vsr as u128 + sol_in as u128)
+}
+
+fn sol_out_u128(vsr: u64, vtr: u64, tokens_in: u64) -> u128 {
+    let k = vsr as u128 * vtr as u128;
+    vsr as u128 - k.div_ceil(vtr as u128 + tokens_in as u128)
+}
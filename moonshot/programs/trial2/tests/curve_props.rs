@@ -0,0 +1,67 @@
+// ─────────────────────────────────────────────────────────────────────────────
+// programs/trial/tests/curve_props.rs
+//
+// Property tests: random buy / sell sequences through calculate_tokens_out,
+// calculate_sol_out and deduct_fee never break the curve invariants
+// (see tests/common/mod.rs for the full list).
+// Run with: cargo test --test curve_props
+// More cases: PROPTEST_CASES=100000 cargo test --release --test curve_props
+//
+// A failure is shrunk to the shortest sequence with the smallest amounts that
+// still breaks, and its seed saved to tests/curve_props.proptest-regressions so
+// it replays first on every later run — commit that file along with the fix.
+// ─────────────────────────────────────────────────────────────────────────────
+
+mod common;
+
+use common::{run, CpModel, Op};
+use proptest::prelude::*;
+use trial2::*;
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        // Dust to well past the graduation threshold
+        prop_oneof![1u64..10_000, 10_000u64..200_000_000_000].prop_map(Op::Buy),
+        (1u16..=10_000).prop_map(Op::Sell),
+    ]
+}
+
+/// The launch reserves, or anything from tiny to near the u64 limit
+fn reserves() -> impl Strategy<Value = (u64, u64)> {
+    prop_oneof![
+        Just((INITIAL_VIRTUAL_SOL_RESERVE, INITIAL_VIRTUAL_TOKEN_RESERVE)),
+        (1u64..=u64::MAX / 2, 1u64..=u64::MAX),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn launch_curve_sequences_hold_invariants(ops in prop::collection::vec(op(), 1..64)) {
+        run(INITIAL_VIRTUAL_SOL_RESERVE, INITIAL_VIRTUAL_TOKEN_RESERVE, &ops);
+    }
+
+    #[test]
+    fn any_reserves_sequences_hold_invariants((vsr, vtr) in reserves(), ops in prop::collection::vec(op(), 1..64)) {
+        run(vsr, vtr, &ops);
+    }
+
+    #[test]
+    fn buy_then_sell_all_is_never_profitable((vsr, vtr) in reserves(), sol_amount in 1u64..u64::MAX / 2) {
+        let mut model = CpModel::new(vsr, vtr);
+        model.buy(sol_amount);
+        model.close();
+    }
+
+    #[test]
+    fn downcasts_never_truncate_at_extreme_reserves(vsr in 1u64.., vtr in 1u64.., amount in 1u64..) {
+        // calculate_* compute in u128 and narrow to u64 at the end
+        let k = vsr as u128 * vtr as u128;
+        let tokens = vtr as u128 - k.div_ceil(vsr as u128 + amount as u128);
+        prop_assert_eq!(calculate_tokens_out(vsr, vtr, amount), Some(tokens as u64));
+        prop_assert!(tokens < vtr as u128);
+
+        let sol = vsr as u128 - k.div_ceil(vtr as u128 + amount as u128);
+        prop_assert_eq!(calculate_sol_out(vsr, vtr, amount), Some(sol as u64));
+        prop_assert!(sol < vsr as u128);
+    }
+}
@@ -0,0 +1,445 @@
+// ─────────────────────────────────────────────────────────────────────────────
+// programs/trial/tests/harness/mod.rs
+//
+// The real program on a solana-program-test bank, shared by the program-level
+// tests (tests/program_flow.rs, tests/pricing_props.rs).
+// Every instruction goes through the Anchor entrypoint with real SPL Token
+// and Associated Token CPIs, so account constraints, transfers and state
+// writes all run exactly as they do on a validator.
+//
+// `cargo test` runs the program natively (patches/solana-invoke lets its CPIs
+// reach the bank). `cargo test-sbf` sets SBF_OUT_DIR and loads
+// target/deploy/trial2.so instead, so compute units are real.
+// ─────────────────────────────────────────────────────────────────────────────
+
+#![allow(dead_code)]
+
+use anchor_lang::prelude::{AccountInfo, Clock, Pubkey, Rent};
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar::SysvarId;
+use anchor_lang::{system_program, AccountDeserialize, AccountSerialize, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{Mint, TokenAccount};
+use solana_account::Account;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_keypair::Keypair;
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+use trial2::*;
+
+/// Lamports every wallet passed to `Chain::start` is funded with
+pub const WALLET_LAMPORTS: u64 = 1_000_000_000_000; // 1,000 SOL
+
+/// Token account size — what a trader's first buy pays rent on
+pub const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// Compute budget every transaction asks for — the per-transaction maximum
+pub const COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+// Anchor's entry ties the slice and its AccountInfos to one lifetime
+fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(accounts.to_vec().into_boxed_slice());
+    trial2::entry(program_id, accounts, data)
+}
+
+/// Whether the program runs from its SBF build — only then are the compute
+/// units `Chain::send` reports what a validator would charge
+pub fn is_sbf() -> bool {
+    std::env::var_os("SBF_OUT_DIR").is_some() || std::env::var_os("BPF_OUT_DIR").is_some()
+}
+
+/// Address of a trial2 PDA
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &trial2::ID).0
+}
+
+/// A bank with the platform already initialized at the default fees
+pub struct Chain {
+    pub ctx: ProgramTestContext,
+    pub admin: Keypair,
+    pub fee_wallet: Pubkey,
+    // Bumped into every compute budget request so repeated instructions
+    // never collide as duplicate transactions
+    nonce: u32,
+}
+
+impl Chain {
+    /// Start a bank with `wallets` funded and the platform config in place.
+    /// The config is written directly: initialize_platform checks the
+    /// upgrade authority, and a program loaded here has no ProgramData.
+    pub async fn start(wallets: &[Pubkey]) -> Self {
+        let mut program = ProgramTest::new("trial2", trial2::ID, processor!(process));
+
+        for wallet in wallets {
+            program.add_account(*wallet, Account {
+                lamports: WALLET_LAMPORTS,
+                owner: system_program::ID,
+                ..Account::default()
+            });
+        }
+
+        let admin = Keypair::new();
+        let fee_wallet = Pubkey::new_unique();
+        let config = PlatformConfig {
+            admin: admin.pubkey(),
+            fee_wallet,
+            fee_bps: PLATFORM_FEE_BPS,
+            grad_threshold: GRADUATION_THRESHOLD,
+            total_tokens: 0,
+            pending_admin: None,
+            paused: false,
+            creator_fee_share_bps: 0,
+            referral_fee_share_bps: 0,
+            total_fees_collected: 0,
+            launch_fee_bps: 0,
+            launch_fee_duration: 0,
+            launch_fee_unit: LaunchFeeUnit::Seconds,
+            creation_fee_lamports: 0,
+            launch_bounds: LaunchBounds::defaults_only(),
+            bump: Pubkey::find_program_address(&[b"platform_config"], &trial2::ID).1,
+        };
+        let mut data = Vec::with_capacity(PlatformConfig::LEN);
+        config.try_serialize(&mut data).unwrap();
+        data.resize(PlatformConfig::LEN, 0);
+        program.add_account(pda(&[b"platform_config"]), Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: trial2::ID,
+            ..Account::default()
+        });
+
+        // initialize_platform funds the fee vault to its rent floor
+        program.add_account(pda(&[b"fee_vault"]), Account {
+            lamports: Rent::default().minimum_balance(0),
+            owner: system_program::ID,
+            ..Account::default()
+        });
+
+        Chain { ctx: program.start_with_context().await, admin, fee_wallet, nonce: 0 }
+    }
+
+    /// Sign and run one instruction, paid by the bank's payer. Ok holds the
+    /// compute units it consumed.
+    pub async fn send(&mut self, ix: Instruction, signers: &[&Keypair]) -> Result<u64, TransactionError> {
+        self.nonce += 1;
+        let budget = ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT - self.nonce);
+
+        let payer = self.ctx.payer.insecure_clone();
+        let mut all_signers = vec![&payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            &[budget, ix],
+            Some(&payer.pubkey()),
+            &all_signers,
+            self.ctx.last_blockhash,
+        );
+
+        let processed = self.ctx.banks_client.process_transaction_with_metadata(tx).await.unwrap();
+        processed.result.map(|()| processed.metadata.map_or(0, |meta| meta.compute_units_consumed))
+    }
+
+    /// Launch a token on `curve_kind` with the default reserves; returns its mint
+    pub async fn create_token(&mut self, creator: &Keypair, curve_kind: CurveKind) -> Pubkey {
+        let mint = Keypair::new();
+        let key = mint.pubkey();
+        let bonding_curve = pda(&[b"bonding_curve", key.as_ref()]);
+
+        let accounts = trial2::accounts::CreateToken {
+            creator: creator.pubkey(),
+            platform_config: pda(&[b"platform_config"]),
+            mint: key,
+            token_meta: pda(&[b"token_meta", key.as_ref()]),
+            bonding_curve,
+            bonding_curve_token_account: get_associated_token_address(&bonding_curve, &key),
+            creator_vault: pda(&[b"creator_vault", key.as_ref()]),
+            sol_vault: pda(&[b"sol_vault", key.as_ref()]),
+            fee_vault: pda(&[b"fee_vault"]),
+            token_program: anchor_spl::token::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+            rent: Rent::id(),
+        };
+        let args = trial2::instruction::CreateToken {
+            name: "Harness".to_string(),
+            symbol: "HRN".to_string(),
+            uri: "https://example.com/harness.json".to_string(),
+            description: String::new(),
+            curve_kind,
+            launch_params: LaunchParams::default(),
+        };
+        let ix = Instruction::new_with_bytes(trial2::ID, &args.data(), accounts.to_account_metas(None));
+
+        self.send(ix, &[creator, &mint]).await.unwrap();
+        key
+    }
+
+    /// `buy(sol_amount, min_tokens_out)` with no referrer
+    pub async fn buy(&mut self, buyer: &Keypair, mint: Pubkey, sol_amount: u64, min_tokens_out: u64) -> Result<u64, TransactionError> {
+        let ix = Instruction::new_with_bytes(
+            trial2::ID,
+            &trial2::instruction::Buy { sol_amount, min_tokens_out }.data(),
+            buy_accounts(buyer.pubkey(), mint, None).to_account_metas(None),
+        );
+        self.send(ix, &[buyer]).await
+    }
+
+    /// `buy(sol_amount, min_tokens_out)` crediting `referrer`
+    pub async fn buy_referred(&mut self, buyer: &Keypair, mint: Pubkey, sol_amount: u64, referrer: Pubkey) -> Result<u64, TransactionError> {
+        let ix = Instruction::new_with_bytes(
+            trial2::ID,
+            &trial2::instruction::Buy { sol_amount, min_tokens_out: 1 }.data(),
+            buy_accounts(buyer.pubkey(), mint, Some(referrer)).to_account_metas(None),
+        );
+        self.send(ix, &[buyer]).await
+    }
+
+    /// `claim_referral_rewards()`, signed by the referrer
+    pub async fn claim_referral_rewards(&mut self, referrer: &Keypair) -> Result<u64, TransactionError> {
+        let accounts = trial2::accounts::ClaimReferralRewards {
+            referrer: referrer.pubkey(),
+            referral_vault: pda(&[b"referral_vault", referrer.pubkey().as_ref()]),
+            system_program: system_program::ID,
+        };
+        let ix = Instruction::new_with_bytes(trial2::ID, &trial2::instruction::ClaimReferralRewards {}.data(), accounts.to_account_metas(None));
+        self.send(ix, &[referrer]).await
+    }
+
+    /// `buy_exact_tokens(token_amount, max_sol_in)` with no referrer
+    pub async fn buy_exact_tokens(&mut self, buyer: &Keypair, mint: Pubkey, token_amount: u64, max_sol_in: u64) -> Result<u64, TransactionError> {
+        let ix = Instruction::new_with_bytes(
+            trial2::ID,
+            &trial2::instruction::BuyExactTokens { token_amount, max_sol_in }.data(),
+            buy_accounts(buyer.pubkey(), mint, None).to_account_metas(None),
+        );
+        self.send(ix, &[buyer]).await
+    }
+
+    /// `sell(token_amount, min_sol_out)` with no referrer
+    pub async fn sell(&mut self, seller: &Keypair, mint: Pubkey, token_amount: u64, min_sol_out: u64) -> Result<u64, TransactionError> {
+        let bonding_curve = pda(&[b"bonding_curve", mint.as_ref()]);
+        let accounts = trial2::accounts::Sell {
+            seller: seller.pubkey(),
+            platform_config: pda(&[b"platform_config"]),
+            mint,
+            bonding_curve,
+            bonding_curve_token_account: get_associated_token_address(&bonding_curve, &mint),
+            seller_token_account: get_associated_token_address(&seller.pubkey(), &mint),
+            fee_vault: pda(&[b"fee_vault"]),
+            creator_vault: pda(&[b"creator_vault", mint.as_ref()]),
+            sol_vault: pda(&[b"sol_vault", mint.as_ref()]),
+            referrer: None,
+            referral_stats: None,
+            referral_vault: None,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        };
+        let args = trial2::instruction::Sell { token_amount, min_sol_out };
+        let ix = Instruction::new_with_bytes(trial2::ID, &args.data(), accounts.to_account_metas(None));
+
+        self.send(ix, &[seller]).await
+    }
+
+    /// `update_platform_config(..)` with `args`, signed by the platform admin
+    pub async fn update_config(&mut self, args: trial2::instruction::UpdatePlatformConfig) -> Result<u64, TransactionError> {
+        let accounts = trial2::accounts::UpdatePlatformConfig {
+            admin: self.admin.pubkey(),
+            platform_config: pda(&[b"platform_config"]),
+        };
+        let ix = Instruction::new_with_bytes(trial2::ID, &args.data(), accounts.to_account_metas(None));
+        let admin = self.admin.insecure_clone();
+        self.send(ix, &[&admin]).await
+    }
+
+    /// `set_token_paused(paused)`, signed by the platform admin
+    pub async fn set_token_paused(&mut self, mint: Pubkey, paused: bool) -> Result<u64, TransactionError> {
+        let accounts = trial2::accounts::SetTokenPaused {
+            admin: self.admin.pubkey(),
+            platform_config: pda(&[b"platform_config"]),
+            mint,
+            bonding_curve: pda(&[b"bonding_curve", mint.as_ref()]),
+        };
+        let ix = Instruction::new_with_bytes(
+            trial2::ID,
+            &trial2::instruction::SetTokenPaused { paused }.data(),
+            accounts.to_account_metas(None),
+        );
+        let admin = self.admin.insecure_clone();
+        self.send(ix, &[&admin]).await
+    }
+
+    /// `migrate_to_pool()`, paid by `payer`
+    pub async fn migrate(&mut self, payer: &Keypair, mint: Pubkey) -> Result<u64, TransactionError> {
+        let bonding_curve = pda(&[b"bonding_curve", mint.as_ref()]);
+        let pool = pda(&[b"pool", mint.as_ref()]);
+        let lp_mint = pda(&[b"lp_mint", mint.as_ref()]);
+        let lp_locker = pda(&[b"lp_locker", mint.as_ref()]);
+        let accounts = trial2::accounts::MigrateToPool {
+            payer: payer.pubkey(),
+            mint,
+            bonding_curve,
+            bonding_curve_token_account: get_associated_token_address(&bonding_curve, &mint),
+            sol_vault: pda(&[b"sol_vault", mint.as_ref()]),
+            pool,
+            lp_mint,
+            pool_token_account: get_associated_token_address(&pool, &mint),
+            lp_locker,
+            locked_lp_account: get_associated_token_address(&lp_locker, &lp_mint),
+            pool_sol_vault: pda(&[b"pool_sol_vault", mint.as_ref()]),
+            token_program: anchor_spl::token::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+        };
+        let ix = Instruction::new_with_bytes(trial2::ID, &trial2::instruction::MigrateToPool {}.data(), accounts.to_account_metas(None));
+        self.send(ix, &[payer]).await
+    }
+
+    /// `swap(amount_in, min_amount_out, direction)` against the mint's pool
+    pub async fn swap(&mut self, trader: &Keypair, mint: Pubkey, amount_in: u64, min_amount_out: u64, direction: SwapDirection) -> Result<u64, TransactionError> {
+        let pool = pda(&[b"pool", mint.as_ref()]);
+        let accounts = trial2::accounts::Swap {
+            trader: trader.pubkey(),
+            platform_config: pda(&[b"platform_config"]),
+            mint,
+            bonding_curve: pda(&[b"bonding_curve", mint.as_ref()]),
+            pool,
+            pool_token_account: get_associated_token_address(&pool, &mint),
+            pool_sol_vault: pda(&[b"pool_sol_vault", mint.as_ref()]),
+            trader_token_account: get_associated_token_address(&trader.pubkey(), &mint),
+            token_program: anchor_spl::token::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+        };
+        let args = trial2::instruction::Swap { amount_in, min_amount_out, direction };
+        let ix = Instruction::new_with_bytes(trial2::ID, &args.data(), accounts.to_account_metas(None));
+        self.send(ix, &[trader]).await
+    }
+
+    /// `remove_liquidity(lp_amount, min_sol_out, min_tokens_out)` from the mint's pool
+    pub async fn remove_liquidity(&mut self, provider: &Keypair, mint: Pubkey, lp_amount: u64, min_sol_out: u64, min_tokens_out: u64) -> Result<u64, TransactionError> {
+        let ix = Instruction::new_with_bytes(
+            trial2::ID,
+            &trial2::instruction::RemoveLiquidity { lp_amount, min_sol_out, min_tokens_out }.data(),
+            liquidity_accounts(provider.pubkey(), mint).to_account_metas(None),
+        );
+        self.send(ix, &[provider]).await
+    }
+
+    /// Move the bank's clock to `unix_timestamp` — trades read it for the
+    /// price accumulator and the launch fee
+    pub async fn set_time(&mut self, unix_timestamp: i64) {
+        let clock = self.ctx.banks_client.get_sysvar::<Clock>().await.unwrap();
+        self.ctx.set_sysvar(&Clock { unix_timestamp, ..clock });
+    }
+
+    /// Rent-exempt minimum for an account of `len` bytes
+    pub async fn rent_for(&mut self, len: usize) -> u64 {
+        self.ctx.banks_client.get_rent().await.unwrap().minimum_balance(len)
+    }
+
+    pub async fn lamports(&mut self, address: Pubkey) -> u64 {
+        self.ctx.banks_client.get_balance(address).await.unwrap()
+    }
+
+    pub async fn curve(&mut self, mint: Pubkey) -> BondingCurve {
+        let account = self.ctx.banks_client.get_account(pda(&[b"bonding_curve", mint.as_ref()])).await.unwrap().unwrap();
+        BondingCurve::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn pool(&mut self, mint: Pubkey) -> Pool {
+        let account = self.ctx.banks_client.get_account(pda(&[b"pool", mint.as_ref()])).await.unwrap().unwrap();
+        Pool::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn mint_account(&mut self, mint: Pubkey) -> Mint {
+        let account = self.ctx.banks_client.get_account(mint).await.unwrap().unwrap();
+        Mint::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    /// Token balance of `owner`'s associated account, 0 if it doesn't exist yet
+    pub async fn token_balance(&mut self, owner: Pubkey, mint: Pubkey) -> u64 {
+        let address = get_associated_token_address(&owner, &mint);
+        match self.ctx.banks_client.get_account(address).await.unwrap() {
+            Some(account) => TokenAccount::try_deserialize(&mut account.data.as_slice()).unwrap().amount,
+            None => 0,
+        }
+    }
+
+    /// Whether `owner` has an associated token account for `mint` yet
+    pub async fn has_token_account(&mut self, owner: Pubkey, mint: Pubkey) -> bool {
+        let address = get_associated_token_address(&owner, &mint);
+        self.ctx.banks_client.get_account(address).await.unwrap().is_some()
+    }
+
+    /// Lamports in the mint's SOL vault above its rent floor — what the curve's
+    /// real_sol_balance must always equal
+    pub async fn vault_reserve(&mut self, mint: Pubkey) -> u64 {
+        let floor = self.rent_for(0).await;
+        self.lamports(pda(&[b"sol_vault", mint.as_ref()])).await - floor
+    }
+}
+
+// Both buy instructions take the same accounts
+fn buy_accounts(buyer: Pubkey, mint: Pubkey, referrer: Option<Pubkey>) -> trial2::accounts::Buy {
+    let bonding_curve = pda(&[b"bonding_curve", mint.as_ref()]);
+    trial2::accounts::Buy {
+        buyer,
+        platform_config: pda(&[b"platform_config"]),
+        mint,
+        bonding_curve,
+        bonding_curve_token_account: get_associated_token_address(&bonding_curve, &mint),
+        buyer_token_account: get_associated_token_address(&buyer, &mint),
+        fee_vault: pda(&[b"fee_vault"]),
+        creator_vault: pda(&[b"creator_vault", mint.as_ref()]),
+        sol_vault: pda(&[b"sol_vault", mint.as_ref()]),
+        referrer,
+        referral_stats: referrer.map(|r| pda(&[b"referral", r.as_ref()])),
+        referral_vault: referrer.map(|r| pda(&[b"referral_vault", r.as_ref()])),
+        token_program: anchor_spl::token::ID,
+        associated_token_program: anchor_spl::associated_token::ID,
+        system_program: system_program::ID,
+    }
+}
+
+// add_liquidity and remove_liquidity take the same accounts
+fn liquidity_accounts(provider: Pubkey, mint: Pubkey) -> trial2::accounts::Liquidity {
+    let pool = pda(&[b"pool", mint.as_ref()]);
+    let lp_mint = pda(&[b"lp_mint", mint.as_ref()]);
+    trial2::accounts::Liquidity {
+        provider,
+        platform_config: pda(&[b"platform_config"]),
+        mint,
+        bonding_curve: pda(&[b"bonding_curve", mint.as_ref()]),
+        pool,
+        lp_mint,
+        pool_token_account: get_associated_token_address(&pool, &mint),
+        pool_sol_vault: pda(&[b"pool_sol_vault", mint.as_ref()]),
+        provider_token_account: get_associated_token_address(&provider, &mint),
+        provider_lp_account: get_associated_token_address(&provider, &lp_mint),
+        token_program: anchor_spl::token::ID,
+        associated_token_program: anchor_spl::associated_token::ID,
+        system_program: system_program::ID,
+    }
+}
+
+/// The Anchor error a failed instruction returned, if that's how it failed.
+/// The program instruction sits after the compute budget one, at index 1.
+pub fn anchor_error(err: &TransactionError) -> Option<u32> {
+    use anchor_lang::solana_program::instruction::error::InstructionError;
+    match err {
+        TransactionError::InstructionError(1, InstructionError::Custom(code)) => Some(*code),
+        _ => None,
+    }
+}
+
+/// Code Anchor reports for `code`
+pub fn code(code: trial2::ErrorCode) -> u32 {
+    code.into()
+}
+
+/// Code Anchor reports when an account constraint fails before the handler runs
+pub fn framework_code(code: anchor_lang::error::ErrorCode) -> u32 {
+    code.into()
+}
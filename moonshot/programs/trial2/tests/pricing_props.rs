@@ -0,0 +1,316 @@
+// ─────────────────────────────────────────────────────────────────────────────
+// programs/trial/tests/pricing_props.rs
+//
+// Property tests: buy / sell run through the real program (tests/harness)
+// never diverge from the pure constant-product functions in curve.rs — the
+// buy that completes the curve included, which fills up to the threshold or
+// the last token and charges only for that — and the price accumulator
+// tracks the prices those trades leave behind.
+// Run with: cargo test --test pricing_props
+//
+// Every case boots its own bank, so these run fewer cases than the pure
+// property tests. More cases: PROPTEST_CASES=256 cargo test --test pricing_props
+// ─────────────────────────────────────────────────────────────────────────────
+
+mod harness;
+
+use anchor_lang::prelude::Pubkey;
+use harness::{anchor_error, code, framework_code, Chain, TOKEN_ACCOUNT_LEN};
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction_error::TransactionError;
+use std::future::Future;
+use trial2::*;
+
+// Helper: run one case's async body to completion
+fn block_on<F: Future>(body: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(body)
+}
+
+/// What a buy of `sol_amount` should do, worked out from the pure
+/// constant-product functions alone
+#[derive(Debug)]
+struct ExpectedBuy {
+    tokens: u64,
+    /// Lamports the buyer pays, fee included — less than `sol_amount` when capped
+    charged: u64,
+    /// Lamports that reach the curve
+    sol_net: u64,
+}
+
+fn expected_buy(curve: &BondingCurve, sol_amount: u64) -> Result<ExpectedBuy, ErrorCode> {
+    let state = curve.state();
+    let sol_room = GRADUATION_THRESHOLD.saturating_sub(state.real_sol_balance);
+    if curve.is_graduated || sol_room == 0 || state.real_token_reserve == 0 {
+        return Err(ErrorCode::TokenGraduated);
+    }
+
+    let (sol_for_curve, _) = deduct_fee(sol_amount);
+    if sol_for_curve == 0 { return Err(ErrorCode::ZeroAmount); }
+
+    // The completing buy fills up to the threshold or the last token
+    let mut sol_net = sol_for_curve.min(sol_room);
+    let mut tokens  = calculate_tokens_out(state.virtual_sol_reserve, state.virtual_token_reserve, sol_net).unwrap();
+    if tokens >= state.real_token_reserve {
+        tokens  = state.real_token_reserve;
+        sol_net = calculate_sol_in(state.virtual_sol_reserve, state.virtual_token_reserve, tokens).unwrap().min(sol_net);
+    }
+    if tokens == 0 { return Err(ErrorCode::ZeroAmount); }
+
+    // ... and charges only for what it filled
+    let charged = if sol_net < sol_for_curve { gross_up_for_fee(sol_net, PLATFORM_FEE_BPS).unwrap() } else { sol_amount };
+    Ok(ExpectedBuy { tokens, charged, sol_net })
+}
+
+/// What a sell of `tokens` should pay: (gross payout, lamports to the seller)
+fn expected_sell(curve: &BondingCurve, tokens: u64) -> Result<(u64, u64), ErrorCode> {
+    if curve.is_graduated { return Err(ErrorCode::TokenGraduated); }
+    if tokens == 0 { return Err(ErrorCode::ZeroAmount); }
+
+    let sol_out = calculate_sol_out(curve.virtual_sol_reserve, curve.virtual_token_reserve, tokens).unwrap_or(0);
+    if sol_out == 0 { return Err(ErrorCode::ZeroAmount); }
+    if sol_out > curve.real_sol_balance { return Err(ErrorCode::InsufficientSol); }
+    Ok((sol_out, deduct_fee(sol_out).0))
+}
+
+// Helper: the instruction failed with Anchor error `expected`
+fn assert_failed_with(result: Result<u64, TransactionError>, expected: u32) -> Result<(), TestCaseError> {
+    match result {
+        Err(err) => prop_assert_eq!(anchor_error(&err), Some(expected), "{:?}", err),
+        Ok(_) => prop_assert!(false, "instruction should have failed with {}", expected),
+    }
+    Ok(())
+}
+
+/// One trader on a freshly launched constant-product curve
+struct Market {
+    chain: Chain,
+    mint: Pubkey,
+    trader: Keypair,
+}
+
+impl Market {
+    async fn launch() -> Self {
+        let (creator, trader) = (Keypair::new(), Keypair::new());
+        let mut chain = Chain::start(&[creator.pubkey(), trader.pubkey()]).await;
+        let mint = chain.create_token(&creator, CurveKind::ConstantProduct).await;
+        Market { chain, mint, trader }
+    }
+
+    // A curve some way along, after a warm-up buy of `warmup` lamports
+    async fn after(warmup: u64) -> Self {
+        let mut market = Market::launch().await;
+        if warmup > 0 {
+            market.buy(warmup).await.unwrap();
+        }
+        market
+    }
+
+    async fn curve(&mut self) -> BondingCurve {
+        self.chain.curve(self.mint).await
+    }
+
+    async fn tokens_held(&mut self) -> u64 {
+        self.chain.token_balance(self.trader.pubkey(), self.mint).await
+    }
+
+    /// Buy through the instruction and check every account it moved against
+    /// `expected_buy`. A rejected buy must leave the curve untouched.
+    async fn buy(&mut self, sol_amount: u64) -> Result<(), TestCaseError> {
+        let trader = self.trader.pubkey();
+        let before = self.curve().await;
+        let (lamports, held) = (self.chain.lamports(trader).await, self.tokens_held().await);
+        let account_rent = if self.chain.has_token_account(trader, self.mint).await { 0 } else { self.chain.rent_for(TOKEN_ACCOUNT_LEN).await };
+
+        let result = self.chain.buy(&self.trader, self.mint, sol_amount, 0).await;
+        let after = self.curve().await;
+
+        let expected = match expected_buy(&before, sol_amount) {
+            Ok(expected) => expected,
+            Err(error) => {
+                assert_failed_with(result, code(error))?;
+                prop_assert_eq!(after.state(), before.state());
+                return Ok(());
+            }
+        };
+        prop_assert!(result.is_ok(), "{:?} should have filled {:?}", result, expected);
+
+        prop_assert_eq!(self.tokens_held().await - held, expected.tokens);
+        prop_assert_eq!(lamports - self.chain.lamports(trader).await, expected.charged + account_rent, "buyer charged {:?}", expected);
+        prop_assert_eq!(after.virtual_sol_reserve, before.virtual_sol_reserve + expected.sol_net);
+        prop_assert_eq!(after.virtual_token_reserve, before.virtual_token_reserve - expected.tokens);
+        prop_assert_eq!(after.real_token_reserve, before.real_token_reserve - expected.tokens);
+        prop_assert_eq!(after.token_total_supply, before.token_total_supply + expected.tokens);
+        prop_assert_eq!(after.real_sol_balance, before.real_sol_balance + expected.sol_net);
+        prop_assert_eq!(self.chain.vault_reserve(self.mint).await, after.real_sol_balance);
+        prop_assert_eq!(after.is_graduated, after.real_sol_balance >= GRADUATION_THRESHOLD || after.real_token_reserve == 0);
+        Ok(())
+    }
+
+    /// Sell through the instruction and check it against `expected_sell`
+    async fn sell(&mut self, tokens: u64) -> Result<(), TestCaseError> {
+        let trader = self.trader.pubkey();
+        let before = self.curve().await;
+        let has_account = self.chain.has_token_account(trader, self.mint).await;
+        let (lamports, held) = (self.chain.lamports(trader).await, self.tokens_held().await);
+
+        let result = self.chain.sell(&self.trader, self.mint, tokens, 0).await;
+        let after = self.curve().await;
+
+        let expected = match expected_sell(&before, tokens) {
+            // Anchor rejects a seller with no token account before the handler runs
+            _ if !has_account => Err(framework_code(anchor_lang::error::ErrorCode::AccountNotInitialized)),
+            Ok(expected) => Ok(expected),
+            Err(error) => Err(code(error)),
+        };
+        let (sol_out, to_seller) = match expected {
+            Ok(expected) => expected,
+            Err(error) => {
+                assert_failed_with(result, error)?;
+                prop_assert_eq!(after.state(), before.state());
+                return Ok(());
+            }
+        };
+        prop_assert!(result.is_ok(), "{:?} should have paid {}", result, sol_out);
+
+        prop_assert_eq!(held - self.tokens_held().await, tokens);
+        prop_assert_eq!(self.chain.lamports(trader).await - lamports, to_seller);
+        prop_assert_eq!(after.virtual_sol_reserve, before.virtual_sol_reserve - sol_out);
+        prop_assert_eq!(after.virtual_token_reserve, before.virtual_token_reserve + tokens);
+        prop_assert_eq!(after.real_sol_balance, before.real_sol_balance - sol_out);
+        prop_assert_eq!(after.token_total_supply, before.token_total_supply - tokens);
+        prop_assert_eq!(self.chain.vault_reserve(self.mint).await, after.real_sol_balance);
+        Ok(())
+    }
+}
+
+/// Up to ~80 SOL of warm-up keeps the curve below the 85 SOL threshold
+fn warmup() -> impl Strategy<Value = u64> {
+    prop_oneof![Just(0u64), 1_000u64..80_000_000_000]
+}
+
+/// Dust to well past what the curve has left to sell
+fn buy_amount() -> impl Strategy<Value = u64> {
+    prop_oneof![1u64..10_000, 10_000u64..5_000_000_000, 5_000_000_000u64..120_000_000_000]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    // ─── 1. Single trades match the pure functions ────────────────────────
+
+    #[test]
+    fn buy_matches_pure_functions(warm in warmup(), sol_amount in buy_amount()) {
+        block_on(async {
+            Market::after(warm).await.buy(sol_amount).await
+        })?;
+    }
+
+    #[test]
+    fn sell_matches_pure_functions(warm in 1_000_000u64..80_000_000_000, fraction in 1u64..=100) {
+        block_on(async {
+            let mut market = Market::after(warm).await;
+            let tokens = (market.tokens_held().await as u128 * fraction as u128 / 100) as u64;
+            market.sell(tokens).await
+        })?;
+    }
+
+    #[test]
+    fn exact_token_buy_delivers_at_least_what_it_charges_for(warm in warmup(), tokens in 1u64..100_000_000_000_000) {
+        block_on(async {
+            let mut market = Market::after(warm).await;
+            let before = market.curve().await;
+            let trader = market.trader.pubkey();
+            let (lamports, held) = (market.chain.lamports(trader).await, market.tokens_held().await);
+            let account_rent = if market.chain.has_token_account(trader, market.mint).await { 0 } else { market.chain.rent_for(TOKEN_ACCOUNT_LEN).await };
+
+            let result = market.chain.buy_exact_tokens(&market.trader, market.mint, tokens, u64::MAX).await;
+            if tokens > before.real_token_reserve {
+                return assert_failed_with(result, code(ErrorCode::InsufficientTokens));
+            }
+            prop_assert!(result.is_ok(), "{:?}", result);
+
+            let after = market.curve().await;
+            let sol_net = after.real_sol_balance - before.real_sol_balance;
+            let charged = lamports - market.chain.lamports(trader).await - account_rent;
+            prop_assert_eq!(market.tokens_held().await - held, tokens);
+            prop_assert!(calculate_tokens_out(before.virtual_sol_reserve, before.virtual_token_reserve, sol_net).unwrap() >= tokens);
+            prop_assert!(charged - fee_for(charged, PLATFORM_FEE_BPS).unwrap() >= sol_net);
+            prop_assert_eq!(market.chain.vault_reserve(market.mint).await, after.real_sol_balance);
+            Ok(())
+        })?;
+    }
+
+    // ─── 2. Trade sequences stay in lockstep ──────────────────────────────
+
+    #[test]
+    fn random_trade_sequence_tracks_pure_reserves(
+        trades in prop::collection::vec((any::<bool>(), buy_amount(), 1u64..=100), 1..40)
+    ) {
+        block_on(async {
+            let mut market = Market::launch().await;
+
+            // Every buy and sell is checked against the pure functions as it
+            // runs — the completing buy and the trades it locks out included
+            for (is_buy, sol_amount, fraction) in trades {
+                let k_before = { let c = market.curve().await; c.virtual_sol_reserve as u128 * c.virtual_token_reserve as u128 };
+                if is_buy {
+                    market.buy(sol_amount).await?;
+                } else {
+                    let tokens = (market.tokens_held().await as u128 * fraction as u128 / 100) as u64;
+                    market.sell(tokens).await?;
+                }
+                let c = market.curve().await;
+                prop_assert!(c.virtual_sol_reserve as u128 * c.virtual_token_reserve as u128 >= k_before, "k shrank");
+            }
+            Ok(())
+        })?;
+    }
+
+    // ─── 3. Price accumulator ─────────────────────────────────────────────
+
+    #[test]
+    fn twap_stays_within_the_prices_it_saw(
+        trades in prop::collection::vec((0i64..600, 1_000u64..3_000_000_000, any::<bool>()), 1..30)
+    ) {
+        block_on(async {
+            let mut market = Market::launch().await;
+            let launch = market.curve().await;
+            let start = launch.observe(launch.last_update_ts);
+            let (mut now, mut expected, mut last) = (launch.last_update_ts, 0u128, launch.last_update_ts);
+            let mut seen = vec![ConstantProduct.spot_price_q64(&launch.state()).unwrap()];
+
+            for (gap, sol_amount, is_buy) in trades {
+                now += gap;
+                market.chain.set_time(now).await;
+                let before = market.curve().await;
+                let price = ConstantProduct.spot_price_q64(&before.state()).unwrap();
+
+                let result = if is_buy {
+                    market.chain.buy(&market.trader, market.mint, sol_amount, 0).await
+                } else {
+                    let half = market.tokens_held().await / 2;
+                    market.chain.sell(&market.trader, market.mint, half, 0).await
+                };
+
+                // A trade accrues the old price up to now; a rejected one (dust,
+                // curve complete) rolls back and leaves the accumulator alone
+                let after = market.curve().await;
+                if result.is_ok() {
+                    expected = expected.wrapping_add(price * (now - last) as u128);
+                    last = now;
+                }
+                prop_assert_eq!(after.price_cumulative, expected);
+                prop_assert_eq!(after.last_update_ts, last);
+                seen.push(ConstantProduct.spot_price_q64(&after.state()).unwrap());
+            }
+
+            let end = market.curve().await.observe(now + 1);
+            let avg = twap(&start, &end).unwrap();
+            prop_assert!(avg >= *seen.iter().min().unwrap() && avg <= *seen.iter().max().unwrap());
+            Ok(())
+        })?;
+    }
+}
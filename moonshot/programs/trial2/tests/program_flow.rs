@@ -0,0 +1,248 @@
+// ─────────────────────────────────────────────────────────────────────────────
+// programs/trial/tests/program_flow.rs
+//
+// Instruction-level tests: create_token, buy, sell and the migration into the
+// pool run through the real program (see tests/harness/mod.rs), and the
+// lamports and tokens they actually move are checked against what the
+// accounts record.
+// Run with: cargo test --test program_flow
+// ─────────────────────────────────────────────────────────────────────────────
+
+mod harness;
+
+use harness::{anchor_error, code, is_sbf, pda, Chain, TOKEN_ACCOUNT_LEN};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use trial2::*;
+
+/// Compute a buy may use — the budget a transaction gets without asking for more
+const MAX_BUY_UNITS: u64 = 200_000;
+
+#[tokio::test]
+async fn buy_then_sell_round_trip_through_vault() {
+    let (creator, trader) = (Keypair::new(), Keypair::new());
+    let mut chain = Chain::start(&[creator.pubkey(), trader.pubkey()]).await;
+    let mint = chain.create_token(&creator, CurveKind::ConstantProduct).await;
+
+    // create_token leaves the vault at its rent floor with nothing raised
+    assert_eq!(chain.curve(mint).await.real_sol_balance, 0);
+    assert_eq!(chain.vault_reserve(mint).await, 0);
+
+    let fee_vault   = pda(&[b"fee_vault"]);
+    let fees_before = chain.lamports(fee_vault).await;
+    let paid_before = chain.lamports(trader.pubkey()).await;
+
+    chain.buy(&trader, mint, 1_000_000_000, 1).await.unwrap();
+
+    let curve  = chain.curve(mint).await;
+    let tokens = chain.token_balance(trader.pubkey(), mint).await;
+    let (sol_net, fee) = deduct_fee(1_000_000_000);
+    assert_eq!(tokens, curve.token_total_supply, "trader holds every token sold");
+    assert_eq!(curve.real_sol_balance, sol_net, "only the post-fee amount is raised");
+    assert_eq!(chain.vault_reserve(mint).await, curve.real_sol_balance, "vault holds exactly what was raised");
+    assert_eq!(chain.lamports(fee_vault).await - fees_before, fee);
+
+    chain.sell(&trader, mint, tokens, 1).await.unwrap();
+
+    // Back where it started: nothing sold, vault at (or a hair above) its floor
+    let curve = chain.curve(mint).await;
+    assert_eq!(curve.token_total_supply, 0);
+    assert_eq!(chain.token_balance(trader.pubkey(), mint).await, 0);
+    assert_eq!(chain.vault_reserve(mint).await, curve.real_sol_balance);
+    assert!(curve.real_sol_balance <= 2, "rounding leaves at most a lamport per trade, not {}", curve.real_sol_balance);
+    assert!(chain.lamports(trader.pubkey()).await < paid_before, "round trip should cost the trader fees");
+}
+
+#[tokio::test]
+async fn many_traders_unwind_without_draining_the_vault() {
+    let traders: Vec<Keypair> = (0..4).map(|_| Keypair::new()).collect();
+    let creator = Keypair::new();
+    let wallets: Vec<_> = traders.iter().chain([&creator]).map(|k| k.pubkey()).collect();
+    let mut chain = Chain::start(&wallets).await;
+    let mint = chain.create_token(&creator, CurveKind::ConstantProduct).await;
+
+    for (trader, sol) in traders.iter().zip([500_000_000u64, 2_000_000_000, 7_500_000_000, 30_000_000_000]) {
+        chain.buy(trader, mint, sol, 1).await.unwrap();
+        assert_eq!(chain.vault_reserve(mint).await, chain.curve(mint).await.real_sol_balance);
+    }
+
+    // Sell back in a different order than bought
+    for trader in traders.iter().rev() {
+        let tokens = chain.token_balance(trader.pubkey(), mint).await;
+        chain.sell(trader, mint, tokens, 1).await.unwrap();
+        assert_eq!(chain.vault_reserve(mint).await, chain.curve(mint).await.real_sol_balance);
+    }
+    assert_eq!(chain.curve(mint).await.token_total_supply, 0);
+}
+
+#[tokio::test]
+async fn worst_case_exponential_buy_fits_the_compute_budget() {
+    let (creator, trader) = (Keypair::new(), Keypair::new());
+    let mut chain = Chain::start(&[creator.pubkey(), trader.pubkey()]).await;
+
+    // Steep enough (20 doublings) that the whole reserve costs ~58 SOL, so a
+    // buy past it runs the integral search to full depth and then reprices the cap
+    let curve_kind = CurveKind::Exponential { start_price: 1_000, doubling_tokens: INITIAL_REAL_TOKEN_RESERVE / 20 };
+    let mint = chain.create_token(&creator, curve_kind).await;
+
+    let units = chain.buy(&trader, mint, 100_000_000_000, 1).await.unwrap();
+
+    let curve = chain.curve(mint).await;
+    assert_eq!(curve.real_token_reserve, 0, "buy should sell out the curve");
+    assert_eq!(chain.vault_reserve(mint).await, curve.real_sol_balance);
+    // A native run doesn't meter compute — cargo test-sbf checks the budget
+    if is_sbf() {
+        assert!(units <= MAX_BUY_UNITS, "worst-case buy used {units} compute units");
+    }
+}
+
+#[tokio::test]
+async fn capped_final_buy_fills_within_a_quoted_slippage_floor() {
+    let (creator, whale, last) = (Keypair::new(), Keypair::new(), Keypair::new());
+    let mut chain = Chain::start(&[creator.pubkey(), whale.pubkey(), last.pubkey()]).await;
+    let mint = chain.create_token(&creator, CurveKind::ConstantProduct).await;
+
+    // 75 SOL raised: a 10 SOL buy still fits under the 85 SOL threshold
+    chain.buy(&whale, mint, gross_up_for_fee(75_000_000_000, PLATFORM_FEE_BPS).unwrap(), 1).await.unwrap();
+    let curve = chain.curve(mint).await;
+    let quote = quote_buy(&curve.curve_kind, &curve.state(), 10_000_000_000, curve.fee_bps, curve.grad_threshold).unwrap();
+    assert_eq!(quote.sol_gross, 10_000_000_000, "quoted uncapped");
+    let min_tokens_out = quote.token_amount * 98 / 100; // 2% slippage
+
+    // Someone else buys first, so the quoted buy now completes the curve
+    chain.buy(&whale, mint, 500_000_000, 1).await.unwrap();
+    let room = GRADUATION_THRESHOLD - chain.curve(mint).await.real_sol_balance;
+
+    let paid_before = chain.lamports(last.pubkey()).await;
+    chain.buy(&last, mint, 10_000_000_000, min_tokens_out).await.unwrap();
+
+    let curve = chain.curve(mint).await;
+    let account_rent = chain.rent_for(TOKEN_ACCOUNT_LEN).await;
+    assert!(curve.is_graduated);
+    assert_eq!(curve.real_sol_balance, GRADUATION_THRESHOLD);
+    assert!(chain.token_balance(last.pubkey(), mint).await < min_tokens_out, "fill is smaller than the quote");
+    assert_eq!(
+        paid_before - chain.lamports(last.pubkey()).await,
+        gross_up_for_fee(room, PLATFORM_FEE_BPS).unwrap() + account_rent,
+        "charged only for the filled part, the rest refunded"
+    );
+}
+
+#[tokio::test]
+async fn migration_moves_everything_into_the_pool_once() {
+    let (creator, whale) = (Keypair::new(), Keypair::new());
+    let mut chain = Chain::start(&[creator.pubkey(), whale.pubkey()]).await;
+    let mint = chain.create_token(&creator, CurveKind::ConstantProduct).await;
+    chain.buy(&whale, mint, 200_000_000_000, 1).await.unwrap();
+    let sold = chain.curve(mint).await.token_total_supply;
+
+    chain.migrate(&creator, mint).await.unwrap();
+
+    let (curve, pool) = (chain.curve(mint).await, chain.pool(mint).await);
+    assert!(curve.is_migrated);
+    assert_eq!((curve.real_sol_balance, curve.real_token_reserve), (0, 0), "curve keeps nothing");
+    assert_eq!(chain.vault_reserve(mint).await, 0);
+    assert_eq!(pool.sol_reserve, GRADUATION_THRESHOLD);
+    assert_eq!(pool.token_reserve, TOKEN_TOTAL_SUPPLY - sold, "unsold tokens plus the rest of the supply");
+    assert_eq!(chain.mint_account(mint).await.supply, TOKEN_TOTAL_SUPPLY);
+
+    // Every LP share exists as a token, the opening ones in the locker
+    let lp_mint = pda(&[b"lp_mint", mint.as_ref()]);
+    assert_eq!(chain.mint_account(lp_mint).await.supply, pool.lp_supply);
+    assert_eq!(chain.token_balance(pda(&[b"lp_locker", mint.as_ref()]), lp_mint).await, pool.locked_lp);
+
+    let err = chain.migrate(&creator, mint).await.unwrap_err();
+    assert_eq!(anchor_error(&err), Some(code(ErrorCode::AlreadyMigrated)));
+}
+
+#[tokio::test]
+async fn pausing_a_token_pauses_its_pool() {
+    let (creator, whale) = (Keypair::new(), Keypair::new());
+    let mut chain = Chain::start(&[creator.pubkey(), whale.pubkey()]).await;
+    let mint = chain.create_token(&creator, CurveKind::ConstantProduct).await;
+    chain.buy(&whale, mint, 200_000_000_000, 1).await.unwrap();
+    chain.migrate(&creator, mint).await.unwrap();
+    chain.swap(&whale, mint, 1_000_000_000, 1, SwapDirection::SolToToken).await.unwrap();
+
+    chain.set_token_paused(mint, true).await.unwrap();
+    let err = chain.swap(&whale, mint, 1_000_000_000, 1, SwapDirection::SolToToken).await.unwrap_err();
+    assert_eq!(anchor_error(&err), Some(code(ErrorCode::Paused)));
+    let err = chain.remove_liquidity(&whale, mint, 1, 0, 0).await.unwrap_err();
+    assert_eq!(anchor_error(&err), Some(code(ErrorCode::Paused)));
+
+    chain.set_token_paused(mint, false).await.unwrap();
+    chain.swap(&whale, mint, 1_000_000_000, 1, SwapDirection::SolToToken).await.unwrap();
+}
+
+#[tokio::test]
+async fn referral_rewards_accrue_on_the_launch_terms_and_are_claimed() {
+    let (creator, trader, referrer) = (Keypair::new(), Keypair::new(), Keypair::new());
+    // The referrer's wallet doesn't exist yet
+    let mut chain = Chain::start(&[creator.pubkey(), trader.pubkey()]).await;
+    let unchanged = trial2::instruction::UpdatePlatformConfig {
+        new_fee_bps: None,
+        new_fee_wallet: None,
+        new_grad_threshold: None,
+        new_creator_fee_share_bps: None,
+        new_referral_fee_share_bps: None,
+        new_creation_fee_lamports: None,
+    };
+    chain.update_config(trial2::instruction::UpdatePlatformConfig {
+        new_creator_fee_share_bps: Some(6_000),
+        new_referral_fee_share_bps: Some(3_000),
+        ..unchanged
+    }).await.unwrap();
+    let mint = chain.create_token(&creator, CurveKind::ConstantProduct).await;
+
+    // Shares that only fit together because the creator's went down
+    chain.update_config(trial2::instruction::UpdatePlatformConfig {
+        new_creator_fee_share_bps: Some(1_000),
+        new_referral_fee_share_bps: Some(9_000),
+        ..unchanged
+    }).await.unwrap();
+
+    // A reward far below rent lands in the vault instead of failing the trade
+    let vault = pda(&[b"referral_vault", referrer.pubkey().as_ref()]);
+    let floor = chain.rent_for(0).await;
+    chain.buy_referred(&trader, mint, 1_000_000, referrer.pubkey()).await.unwrap();
+    let (_, fee) = deduct_fee(1_000_000);
+    let (_, _, reward) = split_fee_with_referral(fee, 6_000, 3_000).unwrap();
+    assert!(reward > 0 && reward < floor);
+    assert_eq!(chain.lamports(vault).await, floor + reward, "launch-time referral share applies");
+
+    chain.buy_referred(&trader, mint, 10_000_000_000, referrer.pubkey()).await.unwrap();
+    let owed = chain.lamports(vault).await - floor;
+    chain.claim_referral_rewards(&referrer).await.unwrap();
+    assert_eq!(chain.lamports(referrer.pubkey()).await, owed);
+    assert_eq!(chain.lamports(vault).await, floor);
+}
+
+#[tokio::test]
+async fn config_updates_reject_out_of_range_values() {
+    let mut chain = Chain::start(&[]).await;
+    let unchanged = trial2::instruction::UpdatePlatformConfig {
+        new_fee_bps: None,
+        new_fee_wallet: None,
+        new_grad_threshold: None,
+        new_creator_fee_share_bps: None,
+        new_referral_fee_share_bps: None,
+        new_creation_fee_lamports: None,
+    };
+
+    let err = chain.update_config(trial2::instruction::UpdatePlatformConfig {
+        new_referral_fee_share_bps: Some(BPS_DENOMINATOR + 1),
+        ..unchanged
+    }).await.unwrap_err();
+    assert_eq!(anchor_error(&err), Some(code(ErrorCode::InvalidFeeBps)));
+
+    let err = chain.update_config(trial2::instruction::UpdatePlatformConfig {
+        new_creation_fee_lamports: Some(MAX_CREATION_FEE_LAMPORTS + 1),
+        ..unchanged
+    }).await.unwrap_err();
+    assert_eq!(anchor_error(&err), Some(code(ErrorCode::InvalidCreationFee)));
+
+    chain.update_config(trial2::instruction::UpdatePlatformConfig {
+        new_creation_fee_lamports: Some(MAX_CREATION_FEE_LAMPORTS),
+        ..unchanged
+    }).await.unwrap();
+}
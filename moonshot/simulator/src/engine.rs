@@ -0,0 +1,335 @@
+// ─────────────────────────────────────────────────────────────────────────────
+// simulator/src/engine.rs
+//
+// Runs trades against one launch exactly the way the program prices them
+// (curve::execute_buy / execute_sell) and keeps the numbers we care about
+// when tuning launch constants: fee revenue, creator earnings, graduation.
+// ─────────────────────────────────────────────────────────────────────────────
+
+use serde::{Deserialize, Serialize};
+use trial2::{
+    current_price_per_token, execute_buy, execute_sell, fully_diluted_market_cap, split_fee, ConstantProduct,
+    CurveState, GRADUATION_THRESHOLD, INITIAL_REAL_TOKEN_RESERVE, INITIAL_VIRTUAL_SOL_RESERVE,
+    INITIAL_VIRTUAL_TOKEN_RESERVE, PLATFORM_FEE_BPS,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+/// One row of a trade log
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trade {
+    pub launch: String,
+    /// Seconds since the launch was created
+    #[serde(default)]
+    pub time: u64,
+    pub side: Side,
+    /// Buy: lamports paid in, fee included. Sell: tokens sold (base units).
+    pub amount: u64,
+}
+
+/// The launch constants under test — defaults are the ones in curve.rs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Params {
+    pub fee_bps: u64,
+    /// Creator's share of each fee, in bps of the fee
+    pub creator_fee_share_bps: u64,
+    pub virtual_sol_reserve: u64,
+    pub virtual_token_reserve: u64,
+    pub real_token_reserve: u64,
+    pub grad_threshold: u64,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            fee_bps: PLATFORM_FEE_BPS,
+            creator_fee_share_bps: 0,
+            virtual_sol_reserve: INITIAL_VIRTUAL_SOL_RESERVE,
+            virtual_token_reserve: INITIAL_VIRTUAL_TOKEN_RESERVE,
+            real_token_reserve: INITIAL_REAL_TOKEN_RESERVE,
+            grad_threshold: GRADUATION_THRESHOLD,
+        }
+    }
+}
+
+impl Params {
+    /// Reject constants no launch could be created with
+    pub fn validate(&self) -> Result<(), String> {
+        if self.fee_bps > 10_000 || self.creator_fee_share_bps > 10_000 {
+            return Err("fee and creator share must be at most 10000 bps".into());
+        }
+        if self.virtual_sol_reserve == 0 || self.virtual_token_reserve == 0 {
+            return Err("virtual reserves must be non-zero".into());
+        }
+        if self.real_token_reserve == 0 || self.real_token_reserve >= self.virtual_token_reserve {
+            return Err("real token reserve must be non-zero and below the virtual token reserve".into());
+        }
+        Ok(())
+    }
+
+    fn initial_state(&self) -> CurveState {
+        CurveState {
+            virtual_sol_reserve: self.virtual_sol_reserve,
+            virtual_token_reserve: self.virtual_token_reserve,
+            real_sol_balance: 0,
+            real_token_reserve: self.real_token_reserve,
+            tokens_sold: 0,
+        }
+    }
+}
+
+/// What happened to one launch. Lamports unless noted.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct LaunchStats {
+    pub launch: String,
+    pub buys: u64,
+    pub sells: u64,
+    /// Trades the program would reject (zero output, selling more than exists, …)
+    pub rejected: u64,
+    /// Trades that arrived after the curve completed
+    pub after_graduation: u64,
+    /// Paid in by buyers, fees included
+    pub buy_volume: u64,
+    /// Paid out of the curve to sellers, before fees
+    pub sell_volume: u64,
+    pub platform_fees: u64,
+    pub creator_fees: u64,
+    /// Handed back by the capped buy that completed the curve
+    pub refunded: u64,
+    pub sol_raised: u64,
+    /// Token base units
+    pub tokens_sold: u64,
+    pub graduated: bool,
+    /// Seconds since launch
+    pub graduated_at: Option<u64>,
+    pub trades_to_graduation: Option<u64>,
+    /// SOL per whole token
+    pub price_at_graduation: Option<f64>,
+    /// SOL per whole token
+    pub final_price: f64,
+    pub fully_diluted_market_cap: u64,
+}
+
+/// One launch being traded
+pub struct Launch {
+    params: Params,
+    state: CurveState,
+    stats: LaunchStats,
+}
+
+impl Launch {
+    pub fn new(id: impl Into<String>, params: Params) -> Self {
+        Launch {
+            params,
+            state: params.initial_state(),
+            stats: LaunchStats { launch: id.into(), ..LaunchStats::default() },
+        }
+    }
+
+    /// Tokens held by traders — the most a sell can return
+    pub fn tokens_sold(&self) -> u64 {
+        self.state.tokens_sold
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.state.real_sol_balance >= self.params.grad_threshold || self.state.real_token_reserve == 0
+    }
+
+    /// Run one trade. Trades the program would reject are counted and skipped.
+    pub fn trade(&mut self, time: u64, side: Side, amount: u64) {
+        if self.stats.graduated {
+            self.stats.after_graduation += 1;
+            return;
+        }
+
+        let p = self.params;
+        let result = match side {
+            Side::Buy => execute_buy(&ConstantProduct, &self.state, amount, p.fee_bps, p.grad_threshold),
+            Side::Sell => execute_sell(&ConstantProduct, &self.state, amount, p.fee_bps),
+        };
+        let Ok(outcome) = result else {
+            self.stats.rejected += 1;
+            return;
+        };
+        let (platform_fee, creator_fee) =
+            split_fee(outcome.fee, p.creator_fee_share_bps).expect("creator share checked by Params::validate");
+
+        let stats = &mut self.stats;
+        match side {
+            Side::Buy => {
+                stats.buys += 1;
+                stats.buy_volume = stats.buy_volume.saturating_add(outcome.sol_amount);
+                stats.refunded = stats.refunded.saturating_add(amount - outcome.sol_amount);
+            }
+            Side::Sell => {
+                stats.sells += 1;
+                stats.sell_volume = stats.sell_volume.saturating_add(outcome.sol_amount);
+            }
+        }
+        stats.platform_fees = stats.platform_fees.saturating_add(platform_fee);
+        stats.creator_fees = stats.creator_fees.saturating_add(creator_fee);
+        self.state = outcome.state;
+
+        if self.is_complete() {
+            let price = self.price();
+            let stats = &mut self.stats;
+            stats.graduated = true;
+            stats.graduated_at = Some(time);
+            stats.trades_to_graduation = Some(stats.buys + stats.sells);
+            stats.price_at_graduation = Some(price);
+        }
+    }
+
+    fn price(&self) -> f64 {
+        current_price_per_token(self.state.virtual_sol_reserve, self.state.virtual_token_reserve)
+    }
+
+    pub fn finish(self) -> LaunchStats {
+        LaunchStats {
+            sol_raised: self.state.real_sol_balance,
+            tokens_sold: self.state.tokens_sold,
+            final_price: self.price(),
+            fully_diluted_market_cap: fully_diluted_market_cap(&ConstantProduct, &self.state).unwrap_or(u64::MAX),
+            ..self.stats
+        }
+    }
+}
+
+/// Totals and averages across every launch
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Summary {
+    pub launches: u64,
+    pub graduated: u64,
+    pub graduation_rate_bps: u64,
+    pub trades: u64,
+    pub rejected: u64,
+    pub buy_volume: u64,
+    pub sell_volume: u64,
+    pub platform_fees: u64,
+    pub creator_fees: u64,
+    /// Averages over graduated launches only
+    pub mean_price_at_graduation: Option<f64>,
+    pub mean_trades_to_graduation: Option<f64>,
+    pub mean_seconds_to_graduation: Option<f64>,
+}
+
+impl Summary {
+    pub fn of(launches: &[LaunchStats]) -> Self {
+        let mut summary = Summary { launches: launches.len() as u64, ..Summary::default() };
+        let (mut price_sum, mut trades_sum, mut seconds_sum) = (0.0, 0u64, 0u64);
+
+        for l in launches {
+            summary.trades += l.buys + l.sells;
+            summary.rejected += l.rejected;
+            summary.buy_volume = summary.buy_volume.saturating_add(l.buy_volume);
+            summary.sell_volume = summary.sell_volume.saturating_add(l.sell_volume);
+            summary.platform_fees = summary.platform_fees.saturating_add(l.platform_fees);
+            summary.creator_fees = summary.creator_fees.saturating_add(l.creator_fees);
+            if l.graduated {
+                summary.graduated += 1;
+                price_sum += l.price_at_graduation.unwrap_or_default();
+                trades_sum += l.trades_to_graduation.unwrap_or_default();
+                seconds_sum += l.graduated_at.unwrap_or_default();
+            }
+        }
+
+        if summary.launches > 0 {
+            summary.graduation_rate_bps = summary.graduated * 10_000 / summary.launches;
+        }
+        if summary.graduated > 0 {
+            let n = summary.graduated as f64;
+            summary.mean_price_at_graduation = Some(price_sum / n);
+            summary.mean_trades_to_graduation = Some(trades_sum as f64 / n);
+            summary.mean_seconds_to_graduation = Some(seconds_sum as f64 / n);
+        }
+        summary
+    }
+}
+
+/// Replay a trade log. Launches are reported in the order they first appear.
+pub fn replay(trades: &[Trade], params: Params) -> Vec<LaunchStats> {
+    let mut launches: Vec<Launch> = Vec::new();
+    for trade in trades {
+        let i = match launches.iter().position(|l| l.stats.launch == trade.launch) {
+            Some(i) => i,
+            None => {
+                launches.push(Launch::new(trade.launch.clone(), params));
+                launches.len() - 1
+            }
+        };
+        launches[i].trade(trade.time, trade.side, trade.amount);
+    }
+    launches.into_iter().map(Launch::finish).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buy(launch: &str, amount: u64) -> Trade {
+        Trade { launch: launch.into(), time: 0, side: Side::Buy, amount }
+    }
+
+    #[test]
+    fn test_fees_split_between_platform_and_creator() {
+        let params = Params { creator_fee_share_bps: 2_500, ..Params::default() };
+        let mut launch = Launch::new("a", params);
+        launch.trade(0, Side::Buy, 10_000_000_000);
+        let stats = launch.finish();
+
+        // 1% of 10 SOL, a quarter of it to the creator
+        assert_eq!(stats.platform_fees + stats.creator_fees, 100_000_000);
+        assert_eq!(stats.creator_fees, 25_000_000);
+        assert_eq!(stats.sol_raised, 9_900_000_000);
+    }
+
+    #[test]
+    fn test_graduation_is_recorded_once_and_later_trades_skipped() {
+        let mut launch = Launch::new("a", Params::default());
+        launch.trade(5, Side::Buy, 50_000_000_000);
+        launch.trade(9, Side::Buy, 50_000_000_000);
+        launch.trade(12, Side::Buy, 1_000_000_000);
+        let stats = launch.finish();
+
+        assert!(stats.graduated);
+        assert_eq!(stats.graduated_at, Some(9));
+        assert_eq!(stats.trades_to_graduation, Some(2));
+        assert_eq!(stats.after_graduation, 1);
+        assert_eq!(stats.sol_raised, GRADUATION_THRESHOLD);
+        assert!(stats.refunded > 0, "the completing buy should have been capped");
+    }
+
+    #[test]
+    fn test_rejected_trades_leave_the_curve_alone() {
+        let mut launch = Launch::new("a", Params::default());
+        launch.trade(0, Side::Sell, 1_000);
+        launch.trade(0, Side::Buy, 0);
+        let stats = launch.finish();
+
+        assert_eq!(stats.rejected, 2);
+        assert_eq!(stats.sol_raised, 0);
+        assert_eq!(stats.final_price, current_price_per_token(INITIAL_VIRTUAL_SOL_RESERVE, INITIAL_VIRTUAL_TOKEN_RESERVE));
+    }
+
+    #[test]
+    fn test_replay_keeps_launches_apart_in_first_seen_order() {
+        let log = [buy("b", 1_000_000_000), buy("a", 2_000_000_000), buy("b", 1_000_000_000)];
+        let stats = replay(&log, Params::default());
+
+        assert_eq!(stats.iter().map(|s| s.launch.as_str()).collect::<Vec<_>>(), ["b", "a"]);
+        assert_eq!(stats[0].buys, 2);
+        assert_eq!(stats[1].buy_volume, 2_000_000_000);
+
+        let summary = Summary::of(&stats);
+        assert_eq!(summary.launches, 2);
+        assert_eq!(summary.trades, 3);
+        assert_eq!(summary.buy_volume, 4_000_000_000);
+        assert_eq!(summary.graduated, 0);
+        assert_eq!(summary.mean_price_at_graduation, None);
+    }
+}
@@ -0,0 +1,184 @@
+// ─────────────────────────────────────────────────────────────────────────────
+// simulator/src/flow.rs
+//
+// Synthetic order flow. Each launch is traded for a fixed window:
+//   - trades arrive as a Poisson process (exponential gaps between them)
+//   - most are retail: a buy sized log-uniformly, or a small partial sell
+//   - some are whale buys, some are panic sells dumping most of the float
+// Everything is drawn from one ChaCha stream, so a seed fixes the output.
+// ─────────────────────────────────────────────────────────────────────────────
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::engine::{Launch, LaunchStats, Params, Side, Trade};
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// Shape of the synthetic market
+#[derive(Clone, Debug, PartialEq)]
+pub struct Flow {
+    pub launches: u32,
+    /// Seconds each launch is traded for
+    pub duration: u64,
+    pub trades_per_minute: f64,
+    /// Chance a retail trade is a buy
+    pub buy_share: f64,
+    /// Retail buy size range, SOL
+    pub retail_sol: (f64, f64),
+    pub whale_chance: f64,
+    /// Whale buy size range, SOL
+    pub whale_sol: (f64, f64),
+    pub panic_chance: f64,
+}
+
+impl Default for Flow {
+    fn default() -> Self {
+        Flow {
+            launches: 100,
+            duration: 3_600,
+            trades_per_minute: 6.0,
+            buy_share: 0.6,
+            retail_sol: (0.01, 2.0),
+            whale_chance: 0.05,
+            whale_sol: (5.0, 25.0),
+            panic_chance: 0.01,
+        }
+    }
+}
+
+impl Flow {
+    pub fn validate(&self) -> Result<(), String> {
+        let chance = |p: f64| (0.0..=1.0).contains(&p);
+        if !(chance(self.buy_share) && chance(self.whale_chance) && chance(self.panic_chance)) {
+            return Err("chances and shares must be between 0 and 1".into());
+        }
+        if self.whale_chance + self.panic_chance > 1.0 {
+            return Err("whale and panic chances add up to more than 1".into());
+        }
+        if self.trades_per_minute <= 0.0 {
+            return Err("trades per minute must be positive".into());
+        }
+        for (lo, hi) in [self.retail_sol, self.whale_sol] {
+            if !(lo > 0.0 && lo <= hi) {
+                return Err("size ranges need 0 < min <= max".into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Generate and run every launch. Returns the stats and the trade log that
+/// produced them, which `engine::replay` reproduces exactly.
+pub fn simulate(seed: u64, flow: &Flow, params: Params) -> (Vec<LaunchStats>, Vec<Trade>) {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut stats = Vec::with_capacity(flow.launches as usize);
+    let mut log = Vec::new();
+
+    for i in 0..flow.launches {
+        let id = format!("launch-{:04}", i);
+        let mut launch = Launch::new(id.clone(), params);
+        let mut t = 0.0;
+
+        loop {
+            t += next_gap(&mut rng, flow.trades_per_minute / 60.0);
+            if t > flow.duration as f64 || launch.is_complete() {
+                break;
+            }
+            let Some((side, amount)) = next_order(&mut rng, flow, &launch) else { continue };
+
+            let time = t as u64;
+            launch.trade(time, side, amount);
+            log.push(Trade { launch: id.clone(), time, side, amount });
+        }
+        stats.push(launch.finish());
+    }
+    (stats, log)
+}
+
+/// Seconds until the next arrival at `rate` per second
+fn next_gap(rng: &mut ChaCha8Rng, rate: f64) -> f64 {
+    // 1 − u is in (0, 1], so the log is finite
+    -(1.0 - rng.gen::<f64>()).ln() / rate
+}
+
+/// A whale buy, a panic sell or a retail trade. None when a sell has nothing to sell.
+fn next_order(rng: &mut ChaCha8Rng, flow: &Flow, launch: &Launch) -> Option<(Side, u64)> {
+    let roll = rng.gen::<f64>();
+    if roll < flow.whale_chance {
+        return Some((Side::Buy, lamports(uniform(rng, flow.whale_sol))));
+    }
+    if roll < flow.whale_chance + flow.panic_chance {
+        return sell_share(launch, rng.gen_range(5_000..=10_000));
+    }
+    if rng.gen::<f64>() < flow.buy_share {
+        return Some((Side::Buy, lamports(log_uniform(rng, flow.retail_sol))));
+    }
+    sell_share(launch, rng.gen_range(50..=1_000))
+}
+
+/// Sell `share_bps` of the tokens traders hold
+fn sell_share(launch: &Launch, share_bps: u64) -> Option<(Side, u64)> {
+    let tokens = (launch.tokens_sold() as u128 * share_bps as u128 / 10_000) as u64;
+    (tokens > 0).then_some((Side::Sell, tokens))
+}
+
+fn uniform(rng: &mut ChaCha8Rng, (lo, hi): (f64, f64)) -> f64 {
+    lo + rng.gen::<f64>() * (hi - lo)
+}
+
+/// Small trades are far more common than large ones
+fn log_uniform(rng: &mut ChaCha8Rng, (lo, hi): (f64, f64)) -> f64 {
+    (lo.ln() + rng.gen::<f64>() * (hi.ln() - lo.ln())).exp()
+}
+
+fn lamports(sol: f64) -> u64 {
+    (sol * LAMPORTS_PER_SOL) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::replay;
+    use trial2::GRADUATION_THRESHOLD;
+
+    fn small_flow() -> Flow {
+        Flow { launches: 8, duration: 1_800, ..Flow::default() }
+    }
+
+    #[test]
+    fn test_same_seed_same_result() {
+        let a = simulate(7, &small_flow(), Params::default());
+        let b = simulate(7, &small_flow(), Params::default());
+        assert_eq!(a, b);
+
+        let c = simulate(8, &small_flow(), Params::default());
+        assert_ne!(a.1, c.1, "a different seed should give different flow");
+    }
+
+    #[test]
+    fn test_replaying_the_generated_log_reproduces_the_stats() {
+        let (stats, log) = simulate(42, &small_flow(), Params::default());
+        assert!(!log.is_empty());
+
+        // Launches that never traded don't appear in the log
+        let traded: Vec<_> = stats.into_iter().filter(|s| s.buys + s.sells + s.rejected > 0).collect();
+        assert_eq!(replay(&log, Params::default()), traded);
+    }
+
+    #[test]
+    fn test_whale_heavy_flow_graduates_launches() {
+        let flow = Flow { launches: 5, whale_chance: 0.5, panic_chance: 0.0, ..Flow::default() };
+        let (stats, _) = simulate(1, &flow, Params::default());
+        assert!(stats.iter().all(|s| s.graduated), "{:?}", stats);
+        assert!(stats.iter().all(|s| s.sol_raised == GRADUATION_THRESHOLD));
+    }
+
+    #[test]
+    fn test_flow_validation() {
+        assert!(Flow::default().validate().is_ok());
+        assert!(Flow { whale_chance: 0.7, panic_chance: 0.5, ..Flow::default() }.validate().is_err());
+        assert!(Flow { retail_sol: (0.0, 1.0), ..Flow::default() }.validate().is_err());
+        assert!(Flow { trades_per_minute: 0.0, ..Flow::default() }.validate().is_err());
+    }
+}
@@ -0,0 +1,77 @@
+// ─────────────────────────────────────────────────────────────────────────────
+// simulator/src/log.rs
+//
+// Trade logs on disk. `.json` files hold an array of trades; anything else is
+// read as CSV with a header row:
+//
+//   launch,time,side,amount
+//   launch-0000,12,buy,1500000000
+//   launch-0000,40,sell,2000000000000
+// ─────────────────────────────────────────────────────────────────────────────
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use crate::engine::Trade;
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+pub fn read_log(path: &Path) -> Result<Vec<Trade>, Box<dyn Error>> {
+    let file = BufReader::new(File::open(path)?);
+    if is_json(path) {
+        return Ok(serde_json::from_reader(file)?);
+    }
+    let mut trades = Vec::new();
+    for row in csv::Reader::from_reader(file).deserialize() {
+        trades.push(row?);
+    }
+    Ok(trades)
+}
+
+pub fn write_log(path: &Path, trades: &[Trade]) -> Result<(), Box<dyn Error>> {
+    let file = BufWriter::new(File::create(path)?);
+    if is_json(path) {
+        serde_json::to_writer_pretty(file, trades)?;
+        return Ok(());
+    }
+    let mut writer = csv::Writer::from_writer(file);
+    for trade in trades {
+        writer.serialize(trade)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Side;
+
+    #[test]
+    fn test_csv_and_json_round_trip() {
+        let trades = vec![
+            Trade { launch: "a".into(), time: 3, side: Side::Buy, amount: 1_500_000_000 },
+            Trade { launch: "b".into(), time: 9, side: Side::Sell, amount: 42 },
+        ];
+        let dir = std::env::temp_dir();
+        for name in ["sim-log-test.csv", "sim-log-test.json"] {
+            let path = dir.join(name);
+            write_log(&path, &trades).unwrap();
+            assert_eq!(read_log(&path).unwrap(), trades, "{}", name);
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_csv_time_column_is_optional() {
+        let path = std::env::temp_dir().join("sim-log-no-time.csv");
+        std::fs::write(&path, "launch,side,amount\na,buy,100\n").unwrap();
+        let trades = read_log(&path).unwrap();
+        assert_eq!(trades, [Trade { launch: "a".into(), time: 0, side: Side::Buy, amount: 100 }]);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,146 @@
+// ─────────────────────────────────────────────────────────────────────────────
+// simulator/src/main.rs
+//
+// Offline launch-economics simulator. Every trade is priced by trial2's
+// curve.rs, exactly as the program would price it, so changing a constant
+// here shows what it would do to fee revenue, creator earnings and graduation.
+//
+//   cargo run -p simulator -- synth --seed 42 --launches 200
+//   cargo run -p simulator -- synth --seed 42 --write-log flow.csv
+//   cargo run -p simulator -- --fee-bps 150 replay flow.csv --json
+//
+// Same seed and flags, same output.
+// ─────────────────────────────────────────────────────────────────────────────
+
+mod engine;
+mod flow;
+mod log;
+mod report;
+
+use std::error::Error;
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+
+use engine::{Params, Summary};
+use flow::Flow;
+
+#[derive(Parser)]
+#[command(name = "simulate", about = "Replay or generate trade flow against the bonding curve")]
+struct Cli {
+    #[command(flatten)]
+    launch: LaunchArgs,
+
+    /// Print JSON instead of a table
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Launch constants — defaults are the ones in curve.rs
+#[derive(Args)]
+struct LaunchArgs {
+    #[arg(long, global = true, default_value_t = Params::default().fee_bps)]
+    fee_bps: u64,
+    /// Creator's share of each fee, bps of the fee
+    #[arg(long, global = true, default_value_t = 0)]
+    creator_share_bps: u64,
+    /// Lamports
+    #[arg(long, global = true, default_value_t = Params::default().virtual_sol_reserve)]
+    virtual_sol: u64,
+    /// Token base units
+    #[arg(long, global = true, default_value_t = Params::default().virtual_token_reserve)]
+    virtual_tokens: u64,
+    /// Token base units
+    #[arg(long, global = true, default_value_t = Params::default().real_token_reserve)]
+    real_tokens: u64,
+    /// Lamports
+    #[arg(long, global = true, default_value_t = Params::default().grad_threshold)]
+    grad_threshold: u64,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Replay a trade log (.json, otherwise CSV)
+    Replay { file: PathBuf },
+    /// Generate synthetic order flow and run it
+    Synth(SynthArgs),
+}
+
+#[derive(Args)]
+struct SynthArgs {
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    #[arg(long, default_value_t = Flow::default().launches)]
+    launches: u32,
+    /// Seconds each launch is traded for
+    #[arg(long, default_value_t = Flow::default().duration)]
+    duration: u64,
+    #[arg(long, default_value_t = Flow::default().trades_per_minute)]
+    trades_per_minute: f64,
+    /// Chance a retail trade is a buy
+    #[arg(long, default_value_t = Flow::default().buy_share)]
+    buy_share: f64,
+    #[arg(long, default_value_t = Flow::default().retail_sol.0)]
+    retail_min_sol: f64,
+    #[arg(long, default_value_t = Flow::default().retail_sol.1)]
+    retail_max_sol: f64,
+    #[arg(long, default_value_t = Flow::default().whale_chance)]
+    whale_chance: f64,
+    #[arg(long, default_value_t = Flow::default().whale_sol.0)]
+    whale_min_sol: f64,
+    #[arg(long, default_value_t = Flow::default().whale_sol.1)]
+    whale_max_sol: f64,
+    #[arg(long, default_value_t = Flow::default().panic_chance)]
+    panic_chance: f64,
+    /// Also save the generated trades, replayable with `replay`
+    #[arg(long)]
+    write_log: Option<PathBuf>,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    let a = &cli.launch;
+    let params = Params {
+        fee_bps: a.fee_bps,
+        creator_fee_share_bps: a.creator_share_bps,
+        virtual_sol_reserve: a.virtual_sol,
+        virtual_token_reserve: a.virtual_tokens,
+        real_token_reserve: a.real_tokens,
+        grad_threshold: a.grad_threshold,
+    };
+    params.validate()?;
+
+    let launches = match cli.command {
+        Command::Replay { file } => engine::replay(&log::read_log(&file)?, params),
+        Command::Synth(s) => {
+            let flow = Flow {
+                launches: s.launches,
+                duration: s.duration,
+                trades_per_minute: s.trades_per_minute,
+                buy_share: s.buy_share,
+                retail_sol: (s.retail_min_sol, s.retail_max_sol),
+                whale_chance: s.whale_chance,
+                whale_sol: (s.whale_min_sol, s.whale_max_sol),
+                panic_chance: s.panic_chance,
+            };
+            flow.validate()?;
+
+            let (launches, trades) = flow::simulate(s.seed, &flow, params);
+            if let Some(path) = s.write_log {
+                log::write_log(&path, &trades)?;
+            }
+            launches
+        }
+    };
+
+    let summary = Summary::of(&launches);
+    if cli.json {
+        println!("{}", report::json(&launches, &summary));
+    } else {
+        print!("{}", report::text(&launches, &summary));
+    }
+    Ok(())
+}
@@ -0,0 +1,73 @@
+// ─────────────────────────────────────────────────────────────────────────────
+// simulator/src/report.rs
+//
+// Per-launch table plus an aggregate block, or the same data as JSON.
+// ─────────────────────────────────────────────────────────────────────────────
+
+use serde::Serialize;
+
+use crate::engine::{LaunchStats, Summary};
+
+#[derive(Serialize)]
+struct Report<'a> {
+    launches: &'a [LaunchStats],
+    summary: &'a Summary,
+}
+
+pub fn json(launches: &[LaunchStats], summary: &Summary) -> String {
+    serde_json::to_string_pretty(&Report { launches, summary }).expect("stats always serialize")
+}
+
+pub fn text(launches: &[LaunchStats], summary: &Summary) -> String {
+    let mut out = format!(
+        "{:<14} {:>6} {:>6} {:>5} {:>12} {:>16} {:>12} {:>12}\n",
+        "launch", "buys", "sells", "grad", "raised SOL", "price@grad SOL", "platform SOL", "creator SOL",
+    );
+    for l in launches {
+        out += &format!(
+            "{:<14} {:>6} {:>6} {:>5} {:>12} {:>16} {:>12} {:>12}\n",
+            l.launch,
+            l.buys,
+            l.sells,
+            if l.graduated { "yes" } else { "no" },
+            sol(l.sol_raised),
+            l.price_at_graduation.map_or("-".into(), |p| format!("{:.10}", p)),
+            sol(l.platform_fees),
+            sol(l.creator_fees),
+        );
+    }
+
+    let mean = |v: Option<f64>, precision: usize| v.map_or("-".into(), |v| format!("{:.*}", precision, v));
+    out += &format!(
+        "\n── summary ──\n\
+         launches            {}\n\
+         graduated           {} ({}.{:02}%)\n\
+         trades              {} ({} rejected)\n\
+         buy volume          {} SOL\n\
+         sell volume         {} SOL\n\
+         platform fees       {} SOL\n\
+         creator fees        {} SOL\n\
+         mean price at grad  {} SOL/token\n\
+         mean trades to grad {}\n\
+         mean time to grad   {} s\n",
+        summary.launches,
+        summary.graduated,
+        summary.graduation_rate_bps / 100,
+        summary.graduation_rate_bps % 100,
+        summary.trades,
+        summary.rejected,
+        sol(summary.buy_volume),
+        sol(summary.sell_volume),
+        sol(summary.platform_fees),
+        sol(summary.creator_fees),
+        mean(summary.mean_price_at_graduation, 10),
+        mean(summary.mean_trades_to_graduation, 1),
+        mean(summary.mean_seconds_to_graduation, 0),
+    );
+    out
+}
+
+/// Lamports as SOL with 4 decimals
+fn sol(lamports: u64) -> String {
+    format!("{}.{:04}", lamports / 1_000_000_000, lamports % 1_000_000_000 / 100_000)
+}
@@ -12,10 +12,14 @@ describe("trial - Day 4: buy and sell", () => {
   const program = anchor.workspace.Smooth as Program<Smooth>;
   const payer = provider.wallet as anchor.Wallet;
 
+  // Null reserves launch with the platform defaults
+  const defaultLaunch = { virtualSolReserve: null, virtualTokenReserve: null, realTokenReserve: null };
+
   const platformConfigSeeds = [Buffer.from("platform_config")];
   const [platformConfigPda] = PublicKey.findProgramAddressSync(platformConfigSeeds, program.programId);
 
-  let feeWallet: PublicKey;  
+  // Platform fees accumulate in the fee vault PDA
+  const [feeVault] = PublicKey.findProgramAddressSync([Buffer.from("fee_vault")], program.programId);
 
   // We'll create one token and reuse it for all tests
   let testMint: Keypair;
@@ -33,11 +37,9 @@ describe("trial - Day 4: buy and sell", () => {
     const balance = await provider.connection.getBalance(payer.publicKey);
   console.log(`Payer balance: ${balance / LAMPORTS_PER_SOL} SOL`)
 
-    // Initialize platform if not done
-    // Fetch the REAL fee wallet from platform config
-    const platformConfig = await program.account.platformConfig.fetch(platformConfigPda);
-    feeWallet = platformConfig.feeWallet;
-    console.log("✓ Using fee wallet:", feeWallet.toBase58());
+    // Platform must already be initialized by the admin suite
+    await program.account.platformConfig.fetch(platformConfigPda);
+    console.log("✓ Using fee vault:", feeVault.toBase58());
     
 
     // Create test token
@@ -60,7 +62,7 @@ describe("trial - Day 4: buy and sell", () => {
     );
 
     await program.methods
-      .createToken("Day 4 Token", "DAY4", "https://test.com/day4", "Testing buy/sell")
+      .createToken("Day 4 Token", "DAY4", "https://test.com/day4", "Testing buy/sell", { constantProduct: {} }, defaultLaunch)
       .accounts({
         creator: payer.publicKey,
         platformConfig: platformConfigPda,
@@ -102,7 +104,7 @@ describe("trial - Day 4: buy and sell", () => {
     const solAmount = 1* LAMPORTS_PER_SOL;
     const minTokensOut = 0;
     const curveBefore = await program.account.bondingCurve.fetch(bondingCurvePda);
-    const feeWalletBalanceBefore = await provider.connection.getBalance(feeWallet);
+    const feeVaultBalanceBefore = await provider.connection.getBalance(feeVault);
 
     console.log("\n=== Before Buy ===");
     console.log("Virtual SOL reserve:", curveBefore.virtualSolReserve.toNumber() / LAMPORTS_PER_SOL, "SOL");
@@ -116,7 +118,7 @@ describe("trial - Day 4: buy and sell", () => {
         bondingCurve: bondingCurvePda,
         bondingCurveTokenAccount: bondingCurveTokenAccount,
         buyerTokenAccount: buyerTokenAccount,
-        feeWallet: feeWallet,
+        feeVault: feeVault,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
@@ -125,14 +127,14 @@ describe("trial - Day 4: buy and sell", () => {
     console.log("buy txns:",tx);
     const curveAfter = await program.account.bondingCurve.fetch(bondingCurvePda);
     const buyerBalance = await provider.connection.getTokenAccountBalance(buyerTokenAccount);
-    const feeWalletBalanceAfter = await provider.connection.getBalance(feeWallet);
+    const feeVaultBalanceAfter = await provider.connection.getBalance(feeVault);
 
     console.log("\n=== After Buy ===");
     console.log("Virtual SOL reserve:", curveAfter.virtualSolReserve.toNumber() / LAMPORTS_PER_SOL, "SOL");
     console.log("Virtual token reserve:", curveAfter.virtualTokenReserve.toString());
     console.log("Real SOL balance:", curveAfter.realSolBalance.toNumber() / LAMPORTS_PER_SOL, "SOL");
     console.log("Buyer token balance:", buyerBalance.value.uiAmountString, "tokens");
-    console.log("Fee collected:", (feeWalletBalanceAfter - feeWalletBalanceBefore) / LAMPORTS_PER_SOL, "SOL");
+    console.log("Fee collected:", (feeVaultBalanceAfter - feeVaultBalanceBefore) / LAMPORTS_PER_SOL, "SOL");
 
     expect(curveAfter.virtualSolReserve.gt(curveBefore.virtualSolReserve)).to.be.true;
     expect(curveAfter.virtualTokenReserve.lt(curveBefore.virtualTokenReserve)).to.be.true;
@@ -141,9 +143,12 @@ describe("trial - Day 4: buy and sell", () => {
     expect(Number(buyerBalance.value.amount)).to.be.greaterThan(0);
 
     const expectedFee = 0.01 * LAMPORTS_PER_SOL;
-    const actualFee = feeWalletBalanceAfter - feeWalletBalanceBefore;
+    const actualFee = feeVaultBalanceAfter - feeVaultBalanceBefore;
     expect(actualFee).to.equal(expectedFee);
 
+    const config = await program.account.platformConfig.fetch(platformConfigPda);
+    expect(config.totalFeesCollected.toNumber()).to.be.at.least(expectedFee);
+
     console.log("all states update verified")
 });
 //test 2 - Buys 0.5 SOL worth of tokens 3 times in a row and Tracks how many tokens are received each time
@@ -169,7 +174,7 @@ it("Price increases with each consecutive buy", async () => {
           bondingCurve: bondingCurvePda,
           bondingCurveTokenAccount: bondingCurveTokenAccount,
           buyerTokenAccount: buyerTokenAccount,
-          feeWallet: feeWallet,
+          feeVault: feeVault,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
@@ -212,7 +217,7 @@ it("Sells tokens and receives SOL back", async () => {
         bondingCurve: bondingCurvePda,
         bondingCurveTokenAccount: bondingCurveTokenAccount,
         sellerTokenAccount: buyerTokenAccount,
-        feeWallet: feeWallet,
+        feeVault: feeVault,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
@@ -269,7 +274,7 @@ it("Round trip (buy then sell) results in net loss due to fees", async () => {
         bondingCurve: bondingCurvePda,
         bondingCurveTokenAccount: bondingCurveTokenAccount,
         buyerTokenAccount: buyerTokenAccount,
-        feeWallet: feeWallet,
+        feeVault: feeVault,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
@@ -292,7 +297,7 @@ it("Round trip (buy then sell) results in net loss due to fees", async () => {
         bondingCurve: bondingCurvePda,
         bondingCurveTokenAccount: bondingCurveTokenAccount,
         sellerTokenAccount: buyerTokenAccount,
-        feeWallet: feeWallet,
+        feeVault: feeVault,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
@@ -341,7 +346,7 @@ it("Round trip (buy then sell) results in net loss due to fees", async () => {
           bondingCurve: bondingCurvePda,
           bondingCurveTokenAccount: bondingCurveTokenAccount,
           buyerTokenAccount: buyerTokenAccount,
-          feeWallet: feeWallet,
+          feeVault: feeVault,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
@@ -378,7 +383,7 @@ it("Round trip (buy then sell) results in net loss due to fees", async () => {
 
     // Create token
     await program.methods
-      .createToken("Graduated Token", "GRAD", "https://test.com/grad", "Will graduate")
+      .createToken("Graduated Token", "GRAD", "https://test.com/grad", "Will graduate", { constantProduct: {} }, defaultLaunch)
       .accounts({
         creator: payer.publicKey,
         platformConfig: platformConfigPda,
@@ -416,7 +421,7 @@ it("Round trip (buy then sell) results in net loss due to fees", async () => {
           bondingCurve: gradCurvePda,
           bondingCurveTokenAccount: gradCurveTokenAccount,
           buyerTokenAccount: buyerTokenAccount,
-          feeWallet: feeWallet,
+          feeVault: feeVault,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
@@ -445,7 +450,7 @@ it("Round trip (buy then sell) results in net loss due to fees", async () => {
           bondingCurve: gradCurvePda,
           bondingCurveTokenAccount: gradCurveTokenAccount,
           buyerTokenAccount: buyerTokenAccount,
-          feeWallet: feeWallet,
+          feeVault: feeVault,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
@@ -475,7 +480,7 @@ it("Round trip (buy then sell) results in net loss due to fees", async () => {
           bondingCurve: bondingCurvePda,
           bondingCurveTokenAccount: bondingCurveTokenAccount,
           buyerTokenAccount: buyerTokenAccount,
-          feeWallet: feeWallet,
+          feeVault: feeVault,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
@@ -489,5 +494,498 @@ it("Round trip (buy then sell) results in net loss due to fees", async () => {
     }
   });
 
+  //paused platform or paused token rejects trading
+  it("Rejects buy while the token or platform is paused", async () => {
+    const buyerTokenAccount = await getAssociatedTokenAddress(
+      testMint.publicKey,
+      payer.publicKey
+    );
+
+    const buyAccounts = {
+      buyer: payer.publicKey,
+      platformConfig: platformConfigPda,
+      mint: testMint.publicKey,
+      bondingCurve: bondingCurvePda,
+      bondingCurveTokenAccount: bondingCurveTokenAccount,
+      buyerTokenAccount: buyerTokenAccount,
+      feeVault: feeVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+
+    // Per-token pause
+    await program.methods
+      .setTokenPaused(true)
+      .accounts({
+        admin: payer.publicKey,
+        platformConfig: platformConfigPda,
+        mint: testMint.publicKey,
+        bondingCurve: bondingCurvePda,
+      })
+      .rpc();
+
+    try {
+      await program.methods
+        .buy(new anchor.BN(0.1 * LAMPORTS_PER_SOL), new anchor.BN(0))
+        .accounts(buyAccounts)
+        .rpc();
+      expect.fail("Should have thrown Paused");
+    } catch (err:any) {
+      expect(err.toString()).to.include("Paused");
+      console.log("✓ Buy on paused token rejected");
+    }
+
+    await program.methods
+      .setTokenPaused(false)
+      .accounts({
+        admin: payer.publicKey,
+        platformConfig: platformConfigPda,
+        mint: testMint.publicKey,
+        bondingCurve: bondingCurvePda,
+      })
+      .rpc();
+
+    // Global pause
+    await program.methods
+      .setPlatformPaused(true)
+      .accounts({
+        admin: payer.publicKey,
+        platformConfig: platformConfigPda,
+      })
+      .rpc();
+
+    try {
+      await program.methods
+        .buy(new anchor.BN(0.1 * LAMPORTS_PER_SOL), new anchor.BN(0))
+        .accounts(buyAccounts)
+        .rpc();
+      expect.fail("Should have thrown Paused");
+    } catch (err:any) {
+      expect(err.toString()).to.include("Paused");
+      console.log("✓ Buy while platform paused rejected");
+    }
+
+    await program.methods
+      .setPlatformPaused(false)
+      .accounts({
+        admin: payer.publicKey,
+        platformConfig: platformConfigPda,
+      })
+      .rpc();
+
+    // Trading resumes once both flags are cleared
+    await program.methods
+      .buy(new anchor.BN(0.1 * LAMPORTS_PER_SOL), new anchor.BN(0))
+      .accounts(buyAccounts)
+      .rpc();
+    console.log("✓ Buy succeeds after unpause");
+  });
+
+  //creator share of the fee accrues in the creator vault and can be claimed
+  it("Accrues creator fees in the vault and lets the creator claim them", async () => {
+    // 50% of the fee goes to the creator for tokens launched after this
+    await program.methods
+      .updatePlatformConfig(null, null, null, new anchor.BN(5_000), null, null)
+      .accounts({
+        admin: payer.publicKey,
+        platformConfig: platformConfigPda,
+      })
+      .rpc();
 
+    const mint = Keypair.generate();
+    const [metaPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_meta"), mint.publicKey.toBuffer()],
+      program.programId
+    );
+    const [curvePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bonding_curve"), mint.publicKey.toBuffer()],
+      program.programId
+    );
+    const [creatorVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("creator_vault"), mint.publicKey.toBuffer()],
+      program.programId
+    );
+    const curveTokenAccount = await getAssociatedTokenAddress(mint.publicKey, curvePda, true);
+
+    await program.methods
+      .createToken("Creator Fee Token", "CFEE", "https://test.com/cfee", "Creator share", { constantProduct: {} }, defaultLaunch)
+      .accounts({
+        creator: payer.publicKey,
+        platformConfig: platformConfigPda,
+        mint: mint.publicKey,
+        tokenMeta: metaPda,
+        bondingCurve: curvePda,
+        bondingCurveTokenAccount: curveTokenAccount,
+        creatorVault: creatorVaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([mint])
+      .rpc();
+
+    // Put the platform default back before anything else can fail
+    await program.methods
+      .updatePlatformConfig(null, null, null, new anchor.BN(0), null, null)
+      .accounts({
+        admin: payer.publicKey,
+        platformConfig: platformConfigPda,
+      })
+      .rpc();
+
+    const vaultBefore = await provider.connection.getBalance(creatorVaultPda);
+    const feeVaultBefore = await provider.connection.getBalance(feeVault);
+
+    await program.methods
+      .buy(new anchor.BN(1 * LAMPORTS_PER_SOL), new anchor.BN(0))
+      .accounts({
+        buyer: payer.publicKey,
+        platformConfig: platformConfigPda,
+        mint: mint.publicKey,
+        bondingCurve: curvePda,
+        bondingCurveTokenAccount: curveTokenAccount,
+        buyerTokenAccount: await getAssociatedTokenAddress(mint.publicKey, payer.publicKey),
+        feeVault: feeVault,
+        creatorVault: creatorVaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const vaultAfter = await provider.connection.getBalance(creatorVaultPda);
+    const feeVaultAfter = await provider.connection.getBalance(feeVault);
+
+    // 1% fee on 1 SOL, split 50/50
+    expect(vaultAfter - vaultBefore).to.equal(0.005 * LAMPORTS_PER_SOL);
+    expect(feeVaultAfter - feeVaultBefore).to.equal(0.005 * LAMPORTS_PER_SOL);
+    console.log("✓ Fee split between platform and creator vault");
+
+    await program.methods
+      .claimCreatorFees()
+      .accounts({
+        creator: payer.publicKey,
+        mint: mint.publicKey,
+        bondingCurve: curvePda,
+        creatorVault: creatorVaultPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Only the rent-exempt floor is left behind
+    expect(await provider.connection.getBalance(creatorVaultPda)).to.equal(vaultBefore);
+    console.log("✓ Creator claimed accrued fees");
+  });
+
+  //referrer's slice of the fee accrues in its vault and its stats PDA tracks volume
+  it("Accrues referral rewards in the referral vault and lets the referrer claim them", async () => {
+    // 20% of the fee to referrers, for tokens launched after this
+    await program.methods
+      .updatePlatformConfig(null, null, null, null, new anchor.BN(2_000), null)
+      .accounts({
+        admin: payer.publicKey,
+        platformConfig: platformConfigPda,
+      })
+      .rpc();
+
+    const mint = Keypair.generate();
+    const [curvePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bonding_curve"), mint.publicKey.toBuffer()],
+      program.programId
+    );
+    const [metaPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_meta"), mint.publicKey.toBuffer()],
+      program.programId
+    );
+    const [creatorVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("creator_vault"), mint.publicKey.toBuffer()],
+      program.programId
+    );
+    const curveTokenAccount = await getAssociatedTokenAddress(mint.publicKey, curvePda, true);
+
+    await program.methods
+      .createToken("Referral Token", "REFR", "https://test.com/refr", "Referral share", { constantProduct: {} }, defaultLaunch)
+      .accounts({
+        creator: payer.publicKey,
+        platformConfig: platformConfigPda,
+        mint: mint.publicKey,
+        tokenMeta: metaPda,
+        bondingCurve: curvePda,
+        bondingCurveTokenAccount: curveTokenAccount,
+        creatorVault: creatorVaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([mint])
+      .rpc();
+
+    // Put the platform default back — the token keeps its launch-time share
+    await program.methods
+      .updatePlatformConfig(null, null, null, null, new anchor.BN(0), null)
+      .accounts({
+        admin: payer.publicKey,
+        platformConfig: platformConfigPda,
+      })
+      .rpc();
+
+    // A brand-new referrer with no SOL at all
+    const referrer = Keypair.generate();
+    const [referralStatsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("referral"), referrer.publicKey.toBuffer()],
+      program.programId
+    );
+    const [referralVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("referral_vault"), referrer.publicKey.toBuffer()],
+      program.programId
+    );
+    const rentFloor = await provider.connection.getMinimumBalanceForRentExemption(0);
+
+    const solAmount = 1 * LAMPORTS_PER_SOL;
+    await program.methods
+      .buy(new anchor.BN(solAmount), new anchor.BN(0))
+      .accounts({
+        buyer: payer.publicKey,
+        platformConfig: platformConfigPda,
+        mint: mint.publicKey,
+        bondingCurve: curvePda,
+        bondingCurveTokenAccount: curveTokenAccount,
+        buyerTokenAccount: await getAssociatedTokenAddress(mint.publicKey, payer.publicKey),
+        feeVault: feeVault,
+        creatorVault: creatorVaultPda,
+        referrer: referrer.publicKey,
+        referralStats: referralStatsPda,
+        referralVault: referralVaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // 1% fee on 1 SOL, 20% of it to the referrer, on top of the vault's rent floor
+    const expectedReward = 0.002 * LAMPORTS_PER_SOL;
+    expect(await provider.connection.getBalance(referralVaultPda)).to.equal(rentFloor + expectedReward);
+
+    const stats = await program.account.referralStats.fetch(referralStatsPda);
+    expect(stats.referrer.toBase58()).to.equal(referrer.publicKey.toBase58());
+    expect(stats.totalVolume.toNumber()).to.equal(solAmount);
+    expect(stats.totalRewards.toNumber()).to.equal(expectedReward);
+
+    await program.methods
+      .claimReferralRewards()
+      .accounts({
+        referrer: referrer.publicKey,
+        referralVault: referralVaultPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([referrer])
+      .rpc();
+
+    expect(await provider.connection.getBalance(referrer.publicKey)).to.equal(expectedReward);
+    expect(await provider.connection.getBalance(referralVaultPda)).to.equal(rentFloor);
+    console.log("✓ Referrer claimed", expectedReward / LAMPORTS_PER_SOL, "SOL");
+  });
+
+  it("Buys an exact token amount and respects max_sol_in", async () => {
+    const buyerTokenAccount = await getAssociatedTokenAddress(testMint.publicKey, payer.publicKey);
+    const accounts = {
+      buyer: payer.publicKey,
+      platformConfig: platformConfigPda,
+      mint: testMint.publicKey,
+      bondingCurve: bondingCurvePda,
+      bondingCurveTokenAccount: bondingCurveTokenAccount,
+      buyerTokenAccount: buyerTokenAccount,
+      feeVault: feeVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+
+    const tokenAmount = new anchor.BN(1_000_000).mul(new anchor.BN(1_000_000)); // 1M tokens
+    const balanceBefore = await provider.connection.getTokenAccountBalance(buyerTokenAccount);
+
+    await program.methods
+      .buyExactTokens(tokenAmount, new anchor.BN(1 * LAMPORTS_PER_SOL))
+      .accounts(accounts)
+      .rpc();
+
+    const balanceAfter = await provider.connection.getTokenAccountBalance(buyerTokenAccount);
+    const received = new anchor.BN(balanceAfter.value.amount).sub(new anchor.BN(balanceBefore.value.amount));
+    expect(received.toString()).to.equal(tokenAmount.toString());
+    console.log("✓ Received exactly", received.toString(), "base units");
+
+    try {
+      await program.methods
+        .buyExactTokens(tokenAmount, new anchor.BN(1))
+        .accounts(accounts)
+        .rpc();
+
+      expect.fail("Should have thrown SlippageExceeded");
+    } catch (err: any) {
+      expect(err.toString()).to.include("SlippageExceeded");
+      console.log("✓ Buy above max_sol_in rejected");
+    }
+  });
+
+  it("Sells for an exact SOL amount and respects max_tokens_in", async () => {
+    const sellerTokenAccount = await getAssociatedTokenAddress(testMint.publicKey, payer.publicKey);
+    const accounts = {
+      seller: payer.publicKey,
+      platformConfig: platformConfigPda,
+      mint: testMint.publicKey,
+      bondingCurve: bondingCurvePda,
+      bondingCurveTokenAccount: bondingCurveTokenAccount,
+      sellerTokenAccount: sellerTokenAccount,
+      feeVault: feeVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+
+    const solOut = 0.01 * LAMPORTS_PER_SOL;
+    const tokensBefore = await provider.connection.getTokenAccountBalance(sellerTokenAccount);
+    const curveBefore = await program.account.bondingCurve.fetch(bondingCurvePda);
+    const solBefore = await provider.connection.getBalance(payer.publicKey);
+
+    await program.methods
+      .sellForExactSol(new anchor.BN(solOut), new anchor.BN(tokensBefore.value.amount))
+      .accounts(accounts)
+      .rpc();
+
+    // Seller nets exactly solOut; the only other change is the signature fee
+    const solAfter = await provider.connection.getBalance(payer.publicKey);
+    expect(solAfter - solBefore).to.be.within(solOut - 10_000, solOut);
+
+    // 1% fee on top: the curve pays out ⌊solOut / 0.99⌋ + 1 in total
+    const curveAfter = await program.account.bondingCurve.fetch(bondingCurvePda);
+    const curvePaid = curveBefore.realSolBalance.sub(curveAfter.realSolBalance).toNumber();
+    expect(curvePaid).to.equal(Math.floor(((solOut - 1) * 10_000) / 9_900) + 1);
+
+    const tokensAfter = await provider.connection.getTokenAccountBalance(sellerTokenAccount);
+    console.log("✓ Sold", (Number(tokensBefore.value.amount) - Number(tokensAfter.value.amount)) / 1e6, "tokens for", solOut / LAMPORTS_PER_SOL, "SOL");
+
+    try {
+      await program.methods
+        .sellForExactSol(new anchor.BN(solOut), new anchor.BN(1))
+        .accounts(accounts)
+        .rpc();
+
+      expect.fail("Should have thrown SlippageExceeded");
+    } catch (err: any) {
+      expect(err.toString()).to.include("SlippageExceeded");
+      console.log("✓ Sell above max_tokens_in rejected");
+    }
+  });
+
+  it("Partially fills the buy that completes the curve and graduates in the same transaction", async () => {
+    const lastMint = Keypair.generate();
+    const [lastMetaPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_meta"), lastMint.publicKey.toBuffer()],
+      program.programId
+    );
+    const [lastCurvePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bonding_curve"), lastMint.publicKey.toBuffer()],
+      program.programId
+    );
+    const lastCurveTokenAccount = await getAssociatedTokenAddress(lastMint.publicKey, lastCurvePda, true);
+
+    await program.methods
+      .createToken("Last Buyer Token", "LAST", "https://test.com/last", "Completes in one buy", { constantProduct: {} }, defaultLaunch)
+      .accounts({
+        creator: payer.publicKey,
+        platformConfig: platformConfigPda,
+        mint: lastMint.publicKey,
+        tokenMeta: lastMetaPda,
+        bondingCurve: lastCurvePda,
+        bondingCurveTokenAccount: lastCurveTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([lastMint])
+      .rpc();
+
+    // Far more than the curve can take before it completes
+    const solAmount = 200 * LAMPORTS_PER_SOL;
+    const balanceBefore = await provider.connection.getBalance(payer.publicKey);
+
+    await program.methods
+      .buy(new anchor.BN(solAmount), new anchor.BN(0))
+      .accounts({
+        buyer: payer.publicKey,
+        platformConfig: platformConfigPda,
+        mint: lastMint.publicKey,
+        bondingCurve: lastCurvePda,
+        bondingCurveTokenAccount: lastCurveTokenAccount,
+        buyerTokenAccount: await getAssociatedTokenAddress(lastMint.publicKey, payer.publicKey),
+        feeVault: feeVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const curve = await program.account.bondingCurve.fetch(lastCurvePda);
+    expect(curve.isGraduated).to.be.true;
+    expect(curve.realSolBalance.toString()).to.equal(curve.gradThreshold.toString());
+
+    // Only the filled part was charged — the fee on ~85 SOL, not 200 SOL
+    const spent = balanceBefore - (await provider.connection.getBalance(payer.publicKey));
+    expect(spent).to.be.lessThan(curve.gradThreshold.toNumber() * 1.2);
+    console.log("✓ Final buy capped at", curve.realSolBalance.toNumber() / LAMPORTS_PER_SOL, "SOL, spent", spent / LAMPORTS_PER_SOL, "SOL");
+  });
+
+  it("Keeps the curve's SOL in its vault PDA, matching real_sol_balance", async () => {
+    const [solVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("sol_vault"), testMint.publicKey.toBuffer()],
+      program.programId
+    );
+    const rentFloor = await provider.connection.getMinimumBalanceForRentExemption(0);
+
+    const curve = await program.account.bondingCurve.fetch(bondingCurvePda);
+    const vaultBalance = await provider.connection.getBalance(solVault);
+    expect(vaultBalance - rentFloor).to.be.at.least(curve.realSolBalance.toNumber());
+    console.log("✓ SOL vault holds", (vaultBalance - rentFloor) / LAMPORTS_PER_SOL, "SOL above rent");
+  });
+
+  it("Accrues the pre-trade spot price into the TWAP accumulator", async () => {
+    const before = await program.account.bondingCurve.fetch(bondingCurvePda);
+    if (before.isGraduated) {
+      console.log("• Token graduated earlier in the suite, skipping");
+      return;
+    }
+
+    // Let the clock move so the current price accrues for a few seconds
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+
+    const buyerTokenAccount = await getAssociatedTokenAddress(testMint.publicKey, payer.publicKey);
+    await program.methods
+      .buy(new anchor.BN(0.01 * LAMPORTS_PER_SOL), new anchor.BN(0))
+      .accounts({
+        buyer: payer.publicKey,
+        platformConfig: platformConfigPda,
+        mint: testMint.publicKey,
+        bondingCurve: bondingCurvePda,
+        bondingCurveTokenAccount: bondingCurveTokenAccount,
+        buyerTokenAccount: buyerTokenAccount,
+        feeVault: feeVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const after = await program.account.bondingCurve.fetch(bondingCurvePda);
+    const elapsed = after.lastUpdateTs.sub(before.lastUpdateTs);
+    expect(elapsed.toNumber()).to.be.greaterThan(0);
+
+    // Constant product spot price in Q64.64 lamports per base unit, held since the last trade
+    const spot = before.virtualSolReserve.shln(64).div(before.virtualTokenReserve);
+    const accrued = after.priceCumulative.sub(before.priceCumulative);
+    expect(accrued.toString()).to.equal(spot.mul(elapsed).toString());
+    console.log("✓ Accumulator grew by", accrued.toString(), "over", elapsed.toString(), "s");
+  });
 });
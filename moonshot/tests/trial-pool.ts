@@ -0,0 +1,330 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Smooth } from "../target/types/smooth";
+import { Keypair, PublicKey, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddress, getMint } from "@solana/spl-token";
+import { expect } from "chai";
+
+describe("trial - post-graduation pool", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Smooth as Program<Smooth>;
+  const payer = provider.wallet as anchor.Wallet;
+
+  // Null reserves launch with the platform defaults
+  const defaultLaunch = { virtualSolReserve: null, virtualTokenReserve: null, realTokenReserve: null };
+
+  const [platformConfigPda] = PublicKey.findProgramAddressSync([Buffer.from("platform_config")], program.programId);
+  const [feeVault] = PublicKey.findProgramAddressSync([Buffer.from("fee_vault")], program.programId);
+
+  const pda = (seed: string, mint: PublicKey) =>
+    PublicKey.findProgramAddressSync([Buffer.from(seed), mint.toBuffer()], program.programId)[0];
+
+  // One token, graduated in setup and migrated by the first test
+  let mint: Keypair;
+  let bondingCurvePda: PublicKey;
+  let bondingCurveTokenAccount: PublicKey;
+  let poolPda: PublicKey;
+  let lpMint: PublicKey;
+  let poolTokenAccount: PublicKey;
+  let poolSolVault: PublicKey;
+  let payerTokenAccount: PublicKey;
+  let payerLpAccount: PublicKey;
+  let rentFloor: number;
+
+  const tokenBalance = async (account: PublicKey) =>
+    new anchor.BN((await provider.connection.getTokenAccountBalance(account)).value.amount);
+
+  async function createToken(name: string, symbol: string): Promise<Keypair> {
+    const newMint = Keypair.generate();
+    const curve = pda("bonding_curve", newMint.publicKey);
+    await program.methods
+      .createToken(name, symbol, "https://test.com/pool", "Pool test token", { constantProduct: {} }, defaultLaunch)
+      .accounts({
+        creator: payer.publicKey,
+        platformConfig: platformConfigPda,
+        mint: newMint.publicKey,
+        tokenMeta: pda("token_meta", newMint.publicKey),
+        bondingCurve: curve,
+        bondingCurveTokenAccount: await getAssociatedTokenAddress(newMint.publicKey, curve, true),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([newMint])
+      .rpc();
+    return newMint;
+  }
+
+  async function migrate(target: PublicKey) {
+    const curve = pda("bonding_curve", target);
+    const pool = pda("pool", target);
+    return program.methods
+      .migrateToPool()
+      .accounts({
+        payer: payer.publicKey,
+        mint: target,
+        bondingCurve: curve,
+        bondingCurveTokenAccount: await getAssociatedTokenAddress(target, curve, true),
+        solVault: pda("sol_vault", target),
+        pool,
+        lpMint: pda("lp_mint", target),
+        poolTokenAccount: await getAssociatedTokenAddress(target, pool, true),
+        lpLocker: pda("lp_locker", target),
+        lockedLpAccount: await getAssociatedTokenAddress(pda("lp_mint", target), pda("lp_locker", target), true),
+        poolSolVault: pda("pool_sol_vault", target),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  const swapAccounts = () => ({
+    trader: payer.publicKey,
+    platformConfig: platformConfigPda,
+    mint: mint.publicKey,
+    bondingCurve: bondingCurvePda,
+    pool: poolPda,
+    poolTokenAccount,
+    poolSolVault,
+    traderTokenAccount: payerTokenAccount,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+  });
+
+  const liquidityAccounts = () => ({
+    provider: payer.publicKey,
+    platformConfig: platformConfigPda,
+    mint: mint.publicKey,
+    bondingCurve: bondingCurvePda,
+    pool: poolPda,
+    lpMint,
+    poolTokenAccount,
+    poolSolVault,
+    providerTokenAccount: payerTokenAccount,
+    providerLpAccount: payerLpAccount,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+  });
+
+  // ── Setup ───────────────────────────────────────────────────────────────────
+
+  before(async () => {
+    console.log("\n=== Setup ===");
+
+    // Platform must already be initialized by the admin suite
+    await program.account.platformConfig.fetch(platformConfigPda);
+    rentFloor = await provider.connection.getMinimumBalanceForRentExemption(0);
+
+    mint = await createToken("Pool Token", "POOL");
+    bondingCurvePda = pda("bonding_curve", mint.publicKey);
+    bondingCurveTokenAccount = await getAssociatedTokenAddress(mint.publicKey, bondingCurvePda, true);
+    poolPda = pda("pool", mint.publicKey);
+    lpMint = pda("lp_mint", mint.publicKey);
+    poolTokenAccount = await getAssociatedTokenAddress(mint.publicKey, poolPda, true);
+    poolSolVault = pda("pool_sol_vault", mint.publicKey);
+    payerTokenAccount = await getAssociatedTokenAddress(mint.publicKey, payer.publicKey);
+    payerLpAccount = await getAssociatedTokenAddress(lpMint, payer.publicKey);
+
+    // One oversized buy fills the curve and graduates it
+    await program.methods
+      .buy(new anchor.BN(200 * LAMPORTS_PER_SOL), new anchor.BN(0))
+      .accounts({
+        buyer: payer.publicKey,
+        platformConfig: platformConfigPda,
+        mint: mint.publicKey,
+        bondingCurve: bondingCurvePda,
+        bondingCurveTokenAccount,
+        buyerTokenAccount: payerTokenAccount,
+        feeVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const curve = await program.account.bondingCurve.fetch(bondingCurvePda);
+    expect(curve.isGraduated).to.be.true;
+    console.log("✓ Token graduated:", mint.publicKey.toBase58());
+  });
+
+  it("Rejects migration before the curve graduates", async () => {
+    const fresh = await createToken("Not Yet", "NYET");
+    try {
+      await migrate(fresh.publicKey);
+      expect.fail("Should have thrown NotGraduated");
+    } catch (err: any) {
+      expect(err.toString()).to.include("NotGraduated");
+      console.log("✓ Migration of a live curve rejected");
+    }
+  });
+
+  it("Migrates the graduated curve into a pool seeded with the rest of the supply", async () => {
+    const curve = await program.account.bondingCurve.fetch(bondingCurvePda);
+    await migrate(mint.publicKey);
+
+    const pool = await program.account.pool.fetch(poolPda);
+    expect(pool.mint.toBase58()).to.equal(mint.publicKey.toBase58());
+    expect(pool.solReserve.toString()).to.equal(curve.realSolBalance.toString());
+
+    // Every token not in holders' hands: the unsold ones plus the rest of the 1B supply
+    const totalSupply = new anchor.BN("1000000000000000");
+    const expectedTokens = totalSupply.sub(curve.tokenTotalSupply);
+    expect(pool.tokenReserve.toString()).to.equal(expectedTokens.toString());
+    expect((await tokenBalance(poolTokenAccount)).toString()).to.equal(expectedTokens.toString());
+    expect((await tokenBalance(bondingCurveTokenAccount)).toNumber()).to.equal(0);
+    expect((await getMint(provider.connection, mint.publicKey)).supply.toString()).to.equal(totalSupply.toString());
+
+    // The curve hands everything over
+    const migrated = await program.account.bondingCurve.fetch(bondingCurvePda);
+    expect(migrated.isMigrated).to.be.true;
+    expect(migrated.realSolBalance.toNumber()).to.equal(0);
+
+    // SOL left the curve's vault and sits on top of the pool vault's rent floor
+    expect(await provider.connection.getBalance(pda("sol_vault", mint.publicKey))).to.equal(rentFloor);
+    expect(await provider.connection.getBalance(poolSolVault)).to.equal(rentFloor + pool.solReserve.toNumber());
+
+    // Opening liquidity is minted to the locker, supply is fixed
+    const lockedLpAccount = await getAssociatedTokenAddress(lpMint, pda("lp_locker", mint.publicKey), true);
+    expect(pool.lpSupply.toString()).to.equal(pool.lockedLp.toString());
+    expect(pool.lockedLp.toNumber()).to.be.greaterThan(0);
+    expect((await getMint(provider.connection, lpMint)).supply.toString()).to.equal(pool.lpSupply.toString());
+    expect((await tokenBalance(lockedLpAccount)).toString()).to.equal(pool.lockedLp.toString());
+    expect((await getMint(provider.connection, mint.publicKey)).mintAuthority).to.be.null;
+
+    console.log("✓ Pool opened with", pool.solReserve.toNumber() / LAMPORTS_PER_SOL, "SOL and", pool.tokenReserve.toString(), "tokens");
+  });
+
+  it("Rejects a second migration", async () => {
+    try {
+      await migrate(mint.publicKey);
+      expect.fail("Should have thrown AlreadyMigrated");
+    } catch (err: any) {
+      expect(err.toString()).to.include("AlreadyMigrated");
+      console.log("✓ Second migration rejected");
+    }
+  });
+
+  it("Swaps SOL for tokens at the constant-product price", async () => {
+    const before = await program.account.pool.fetch(poolPda);
+    const tokensBefore = await tokenBalance(payerTokenAccount);
+    const solIn = new anchor.BN(LAMPORTS_PER_SOL);
+
+    await program.methods
+      .swap(solIn, new anchor.BN(0), { solToToken: {} })
+      .accounts(swapAccounts())
+      .rpc();
+
+    // out = reserve_out × in × (10000 − fee) / (reserve_in × 10000 + in × (10000 − fee))
+    const inAfterFee = solIn.mul(new anchor.BN(10_000).sub(before.feeBps));
+    const expectedOut = before.tokenReserve
+      .mul(inAfterFee)
+      .div(before.solReserve.mul(new anchor.BN(10_000)).add(inAfterFee));
+
+    const received = (await tokenBalance(payerTokenAccount)).sub(tokensBefore);
+    expect(received.toString()).to.equal(expectedOut.toString());
+
+    const after = await program.account.pool.fetch(poolPda);
+    expect(after.solReserve.toString()).to.equal(before.solReserve.add(solIn).toString());
+    expect(after.tokenReserve.toString()).to.equal(before.tokenReserve.sub(expectedOut).toString());
+    console.log("✓ 1 SOL bought", received.toString(), "tokens");
+  });
+
+  it("Swaps tokens back for SOL", async () => {
+    const before = await program.account.pool.fetch(poolPda);
+    const tokensIn = (await tokenBalance(payerTokenAccount)).div(new anchor.BN(100));
+    const solBefore = await provider.connection.getBalance(payer.publicKey);
+
+    await program.methods
+      .swap(tokensIn, new anchor.BN(1), { tokenToSol: {} })
+      .accounts(swapAccounts())
+      .rpc();
+
+    const after = await program.account.pool.fetch(poolPda);
+    const solOut = before.solReserve.sub(after.solReserve);
+    expect(solOut.toNumber()).to.be.greaterThan(0);
+    expect(after.tokenReserve.toString()).to.equal(before.tokenReserve.add(tokensIn).toString());
+    expect(await provider.connection.getBalance(poolSolVault)).to.equal(rentFloor + after.solReserve.toNumber());
+
+    // Received the SOL, minus the transaction fee
+    const gained = (await provider.connection.getBalance(payer.publicKey)) - solBefore;
+    expect(gained).to.be.greaterThan(solOut.toNumber() - 10_000);
+    console.log("✓ Sold", tokensIn.toString(), "tokens for", solOut.toNumber() / LAMPORTS_PER_SOL, "SOL");
+  });
+
+  it("Rejects a swap below min_amount_out", async () => {
+    try {
+      await program.methods
+        .swap(new anchor.BN(LAMPORTS_PER_SOL), new anchor.BN("18446744073709551615"), { solToToken: {} })
+        .accounts(swapAccounts())
+        .rpc();
+      expect.fail("Should have thrown SlippageExceeded");
+    } catch (err: any) {
+      expect(err.toString()).to.include("SlippageExceeded");
+      console.log("✓ Slippage protection works on swaps");
+    }
+  });
+
+  it("Adds liquidity in the pool's ratio and mints LP tokens", async () => {
+    const before = await program.account.pool.fetch(poolPda);
+    const lpAmount = before.lpSupply.div(new anchor.BN(20));
+
+    await program.methods
+      .addLiquidity(lpAmount, new anchor.BN(10 * LAMPORTS_PER_SOL), new anchor.BN("18446744073709551615"))
+      .accounts(liquidityAccounts())
+      .rpc();
+
+    const after = await program.account.pool.fetch(poolPda);
+    expect((await tokenBalance(payerLpAccount)).toString()).to.equal(lpAmount.toString());
+    expect(after.lpSupply.toString()).to.equal(before.lpSupply.add(lpAmount).toString());
+
+    // Deposits round up: a 5% share costs at least 5% of each reserve
+    const solIn = after.solReserve.sub(before.solReserve);
+    const tokensIn = after.tokenReserve.sub(before.tokenReserve);
+    expect(solIn.mul(before.lpSupply).gte(lpAmount.mul(before.solReserve))).to.be.true;
+    expect(tokensIn.mul(before.lpSupply).gte(lpAmount.mul(before.tokenReserve))).to.be.true;
+    expect(await provider.connection.getBalance(poolSolVault)).to.equal(rentFloor + after.solReserve.toNumber());
+    console.log("✓ Deposited", solIn.toNumber() / LAMPORTS_PER_SOL, "SOL and", tokensIn.toString(), "tokens for", lpAmount.toString(), "LP");
+  });
+
+  it("Removes liquidity by burning LP tokens", async () => {
+    const before = await program.account.pool.fetch(poolPda);
+    const lpAmount = await tokenBalance(payerLpAccount);
+    const tokensBefore = await tokenBalance(payerTokenAccount);
+
+    await program.methods
+      .removeLiquidity(lpAmount, new anchor.BN(0), new anchor.BN(0))
+      .accounts(liquidityAccounts())
+      .rpc();
+
+    const after = await program.account.pool.fetch(poolPda);
+    expect((await tokenBalance(payerLpAccount)).toNumber()).to.equal(0);
+    expect(after.lpSupply.toString()).to.equal(before.lockedLp.toString());
+
+    // Withdrawals round down
+    const solOut = before.solReserve.sub(after.solReserve);
+    const tokensOut = (await tokenBalance(payerTokenAccount)).sub(tokensBefore);
+    expect(tokensOut.toString()).to.equal(before.tokenReserve.sub(after.tokenReserve).toString());
+    expect(solOut.mul(before.lpSupply).lte(lpAmount.mul(before.solReserve))).to.be.true;
+    expect(await provider.connection.getBalance(poolSolVault)).to.equal(rentFloor + after.solReserve.toNumber());
+    console.log("✓ Withdrew", solOut.toNumber() / LAMPORTS_PER_SOL, "SOL and", tokensOut.toString(), "tokens");
+  });
+
+  it("Rejects removing more LP than the provider holds", async () => {
+    try {
+      await program.methods
+        .removeLiquidity(new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+        .accounts(liquidityAccounts())
+        .rpc();
+      expect.fail("Should have thrown InsufficientTokens");
+    } catch (err: any) {
+      expect(err.toString()).to.include("InsufficientTokens");
+      console.log("✓ Locked liquidity stays in the pool");
+    }
+  });
+});
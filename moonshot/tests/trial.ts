@@ -9,7 +9,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Smooth } from "../target/types/smooth";
-import { Keypair, PublicKey, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { Keypair, PublicKey, SystemProgram, LAMPORTS_PER_SOL, BPF_LOADER_UPGRADEABLE_PROGRAM_ID } from "@solana/web3.js";
 import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddress } from "@solana/spl-token";
 import { expect } from "chai";
 
@@ -20,10 +20,19 @@ describe("trial - Day 3: create_token", () => {
   const program = anchor.workspace.Smooth as Program<Smooth>;
   const payer = provider.wallet as anchor.Wallet;
 
+  // Null reserves launch with the platform defaults
+  const defaultLaunch = { virtualSolReserve: null, virtualTokenReserve: null, realTokenReserve: null };
+
   // PDA seeds — must match lib.rs
   const platformConfigSeeds = [Buffer.from("platform_config")];
   const [platformConfigPda] = PublicKey.findProgramAddressSync(platformConfigSeeds, program.programId);
 
+  // ProgramData account — initialize_platform checks its upgrade authority
+  const [programDataPda] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+  );
+
   // fee wallet to receive platform fees
   const feeWallet = Keypair.generate();
 
@@ -47,6 +56,8 @@ describe("trial - Day 3: create_token", () => {
           admin: payer.publicKey,
           platformConfig: platformConfigPda,
           feeWallet: feeWallet.publicKey,
+          program: program.programId,
+          programData: programDataPda,
           systemProgram: SystemProgram.programId,
         })
         .rpc();
@@ -93,7 +104,7 @@ describe("trial - Day 3: create_token", () => {
 
     // Call create_token
     const tx = await program.methods
-      .createToken(name, symbol, uri, description)
+      .createToken(name, symbol, uri, description, { constantProduct: {} }, defaultLaunch)
       .accounts({
         creator: payer.publicKey,
         platformConfig: platformConfigPda,
@@ -142,6 +153,13 @@ describe("trial - Day 3: create_token", () => {
     expect(bondingCurve.virtualSolReserve.toNumber()).to.equal(30 * LAMPORTS_PER_SOL);
     expect(bondingCurve.virtualTokenReserve.toString()).to.equal("1073000000000000"); // 1.073B with 6 decimals
 
+    // Launch terms are snapshotted from the platform config at creation
+    const configAtCreation = await program.account.platformConfig.fetch(platformConfigPda);
+    expect(bondingCurve.feeBps.toNumber()).to.equal(configAtCreation.feeBps.toNumber());
+    expect(bondingCurve.gradThreshold.toString()).to.equal(configAtCreation.gradThreshold.toString());
+    expect(bondingCurve.initialVirtualSolReserve.toString()).to.equal(bondingCurve.virtualSolReserve.toString());
+    expect(bondingCurve.initialVirtualTokenReserve.toString()).to.equal(bondingCurve.virtualTokenReserve.toString());
+
     console.log("✓ BondingCurve verified");
     console.log("  - Virtual SOL reserve:", bondingCurve.virtualSolReserve.toNumber() / LAMPORTS_PER_SOL, "SOL");
     console.log("  - Virtual token reserve:", bondingCurve.virtualTokenReserve.toString());
@@ -166,8 +184,8 @@ describe("trial - Day 3: create_token", () => {
     // ── Verify platform total_tokens counter incremented ─────────────────────
 
     const platformConfig = await program.account.platformConfig.fetch(platformConfigPda);
-    //expect(platformConfig.totalTokens.toNumber()).to.be.greaterThan(0);
-    console.log("⚠️ Platform total_tokens check skipped");
+    expect(platformConfig.totalTokens.toNumber()).to.be.greaterThan(0);
+    console.log("✓ Platform total_tokens:", platformConfig.totalTokens.toNumber());
   });
 
   // ── Test 2: Name validation ─────────────────────────────────────────────────
@@ -193,7 +211,7 @@ describe("trial - Day 3: create_token", () => {
 
     try {
       await program.methods
-        .createToken("", "TEST", "https://test.com", "desc")
+        .createToken("", "TEST", "https://test.com", "desc", { constantProduct: {} }, defaultLaunch)
         .accounts({
           creator: payer.publicKey,
           platformConfig: platformConfigPda,  // ✅ FIXED: underscore
@@ -241,7 +259,7 @@ describe("trial - Day 3: create_token", () => {
 
     try {
       await program.methods
-        .createToken(tooLongName, "TEST", "https://test.com", "desc")
+        .createToken(tooLongName, "TEST", "https://test.com", "desc", { constantProduct: {} }, defaultLaunch)
         .accounts({
           creator: payer.publicKey,
           platformConfig: platformConfigPda,  // ✅ FIXED: underscore
@@ -289,7 +307,7 @@ describe("trial - Day 3: create_token", () => {
       );
 
       await program.methods
-        .createToken(`Token ${i + 1}`, `TK${i + 1}`, `https://test${i}.com`, `Description ${i}`)
+        .createToken(`Token ${i + 1}`, `TK${i + 1}`, `https://test${i}.com`, `Description ${i}`, { constantProduct: {} }, defaultLaunch)
         .accounts({
           creator: payer.publicKey,
           platformConfig: platformConfigPda,
@@ -324,4 +342,205 @@ describe("trial - Day 3: create_token", () => {
 
     console.log("✓ All 3 tokens have separate TokenMeta accounts");
   });
-});
\ No newline at end of file
+
+  // ── Test 5: Creation fee goes to the fee vault ──────────────────────────────
+
+  it("Charges the creation fee into the fee vault", async () => {
+    const creationFee = 0.05 * LAMPORTS_PER_SOL;
+    const [feeVault] = PublicKey.findProgramAddressSync([Buffer.from("fee_vault")], program.programId);
+
+    await program.methods
+      .updatePlatformConfig(null, null, null, null, null, new anchor.BN(creationFee))
+      .accounts({
+        admin: payer.publicKey,
+        platformConfig: platformConfigPda,
+      })
+      .rpc();
+
+    const mint = Keypair.generate();
+    const [tokenMetaPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_meta"), mint.publicKey.toBuffer()],
+      program.programId
+    );
+    const [bondingCurvePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bonding_curve"), mint.publicKey.toBuffer()],
+      program.programId
+    );
+    const bondingCurveTokenAccount = await getAssociatedTokenAddress(mint.publicKey, bondingCurvePda, true);
+
+    const vaultBefore = await provider.connection.getBalance(feeVault);
+
+    try {
+      await program.methods
+        .createToken("Paid Token", "PAID", "https://test.com/paid", "Pays the creation fee", { constantProduct: {} }, defaultLaunch)
+        .accounts({
+          creator: payer.publicKey,
+          platformConfig: platformConfigPda,
+          mint: mint.publicKey,
+          tokenMeta: tokenMetaPda,
+          bondingCurve: bondingCurvePda,
+          bondingCurveTokenAccount: bondingCurveTokenAccount,
+          feeVault,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([mint])
+        .rpc();
+    } finally {
+      // Keep launches free for the other suites
+      await program.methods
+        .updatePlatformConfig(null, null, null, null, null, new anchor.BN(0))
+        .accounts({
+          admin: payer.publicKey,
+          platformConfig: platformConfigPda,
+        })
+        .rpc();
+    }
+
+    const vaultAfter = await provider.connection.getBalance(feeVault);
+    expect(vaultAfter - vaultBefore).to.equal(creationFee);
+    console.log("✓ Creation fee collected:", creationFee / LAMPORTS_PER_SOL, "SOL");
+  });
+
+  // ── Test 6: Curve shape is chosen at creation ───────────────────────────────
+
+  it("Stores the chosen curve kind and rejects invalid curve params", async () => {
+    const createWith = async (curveKind: any) => {
+      const mint = Keypair.generate();
+      const [tokenMetaPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("token_meta"), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [bondingCurvePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bonding_curve"), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const bondingCurveTokenAccount = await getAssociatedTokenAddress(mint.publicKey, bondingCurvePda, true);
+
+      await program.methods
+        .createToken("Linear Token", "LIN", "https://test.com/linear", "Linear curve", curveKind, defaultLaunch)
+        .accounts({
+          creator: payer.publicKey,
+          platformConfig: platformConfigPda,
+          mint: mint.publicKey,
+          tokenMeta: tokenMetaPda,
+          bondingCurve: bondingCurvePda,
+          bondingCurveTokenAccount: bondingCurveTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([mint])
+        .rpc();
+
+      return bondingCurvePda;
+    };
+
+    const linear = { linear: { startPrice: new anchor.BN(27_958_993), endPrice: new anchor.BN(400_000_000) } };
+    const curvePda = await createWith(linear);
+    const curve = await program.account.bondingCurve.fetch(curvePda);
+    expect(curve.curveKind.linear.endPrice.toNumber()).to.equal(400_000_000);
+    console.log("✓ Linear curve stored on BondingCurve");
+
+    try {
+      // End price below start price is not a rising curve
+      await createWith({ linear: { startPrice: new anchor.BN(10), endPrice: new anchor.BN(5) } });
+      expect.fail("Should have thrown InvalidCurveParams");
+    } catch (err: any) {
+      expect(err.toString()).to.include("InvalidCurveParams");
+      console.log("✓ Invalid curve params rejected");
+    }
+
+    try {
+      // Doubles every base unit — overflows long before the reserve sells out
+      await createWith({ exponential: { startPrice: new anchor.BN(1), doublingTokens: new anchor.BN(1) } });
+      expect.fail("Should have thrown InvalidCurveParams");
+    } catch (err: any) {
+      expect(err.toString()).to.include("InvalidCurveParams");
+      console.log("✓ Curve that can't sell out rejected");
+    }
+  });
+
+  // ── Test 7: Custom launch reserves must sit inside the admin bounds ─────────
+
+  it("Launches with custom reserves inside the admin bounds and rejects the rest", async () => {
+    const createWith = async (launch: any) => {
+      const mint = Keypair.generate();
+      const [tokenMetaPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("token_meta"), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [bondingCurvePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bonding_curve"), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const bondingCurveTokenAccount = await getAssociatedTokenAddress(mint.publicKey, bondingCurvePda, true);
+
+      await program.methods
+        .createToken("Custom Token", "CUST", "https://test.com/custom", "Custom reserves", { constantProduct: {} }, launch)
+        .accounts({
+          creator: payer.publicKey,
+          platformConfig: platformConfigPda,
+          mint: mint.publicKey,
+          tokenMeta: tokenMetaPda,
+          bondingCurve: bondingCurvePda,
+          bondingCurveTokenAccount: bondingCurveTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([mint])
+        .rpc();
+
+      return { bondingCurvePda, bondingCurveTokenAccount };
+    };
+
+    const before = await program.account.platformConfig.fetch(platformConfigPda);
+    const bounds = {
+      minVirtualSolReserve: new anchor.BN(10 * LAMPORTS_PER_SOL),
+      maxVirtualSolReserve: new anchor.BN(60 * LAMPORTS_PER_SOL),
+      minVirtualTokenReserve: new anchor.BN("500000000000000"),
+      maxVirtualTokenReserve: new anchor.BN("1073000000000000"),
+      minRealTokenReserve: new anchor.BN("400000000000000"),
+      maxRealTokenReserve: new anchor.BN("793100000000000"),
+    };
+
+    await program.methods
+      .setLaunchBounds(bounds)
+      .accounts({ admin: payer.publicKey, platformConfig: platformConfigPda })
+      .rpc();
+
+    try {
+      const realTokenReserve = new anchor.BN("400000000000000");
+      const { bondingCurvePda, bondingCurveTokenAccount } = await createWith({
+        virtualSolReserve: new anchor.BN(20 * LAMPORTS_PER_SOL),
+        virtualTokenReserve: new anchor.BN("600000000000000"),
+        realTokenReserve,
+      });
+
+      const curve = await program.account.bondingCurve.fetch(bondingCurvePda);
+      expect(curve.virtualSolReserve.toNumber()).to.equal(20 * LAMPORTS_PER_SOL);
+      expect(curve.realTokenReserve.toString()).to.equal(realTokenReserve.toString());
+      const balance = await provider.connection.getTokenAccountBalance(bondingCurveTokenAccount);
+      expect(balance.value.amount).to.equal(realTokenReserve.toString());
+      console.log("✓ Custom reserves stored and minted");
+
+      try {
+        await createWith({ ...defaultLaunch, virtualSolReserve: new anchor.BN(100 * LAMPORTS_PER_SOL) });
+        expect.fail("Should have thrown InvalidLaunchParams");
+      } catch (err: any) {
+        expect(err.toString()).to.include("InvalidLaunchParams");
+        console.log("✓ Out of bounds reserves rejected");
+      }
+    } finally {
+      await program.methods
+        .setLaunchBounds(before.launchBounds)
+        .accounts({ admin: payer.publicKey, platformConfig: platformConfigPda })
+        .rpc();
+    }
+  });
+});